@@ -0,0 +1,189 @@
+use std::collections::BinaryHeap;
+
+use crate::{dijkstra::Pending, layered::Label, LayeredGraph, MinPathError, Path, Weight};
+
+/// Estimate of the least cost from a node to the target of an A* search, see
+/// `LayeredGraph::a_star`.
+///
+/// The path found is only guaranteed to be a least cost one if estimates are
+/// consistent: no estimate exceeds the weight of an edge, and of the node it
+/// leads to, plus the estimate at that node, and the target's estimate is 0.
+/// Consistent estimates never exceed the actual least cost.
+pub trait Heuristic<W> {
+	/// Estimated least cost from the node at the given row and column.
+	fn estimate(&self, row: usize, column: usize) -> W;
+}
+
+impl<W, F: Fn(usize, usize) -> W> Heuristic<W> for F {
+	fn estimate(&self, row: usize, column: usize) -> W {
+		self(row, column)
+	}
+}
+
+/// Consistent heuristic for any target in the last row: the sum, over the rows
+/// left to go through, of the least weight of an edge out of the row plus that
+/// of the node it leads to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowMinimum<W> {
+	/// `remaining[r]` is the estimate for the nodes of row `r`.
+	remaining: Vec<W>,
+}
+
+impl<W: Weight> RowMinimum<W> {
+	pub fn new(graph: &LayeredGraph<W>) -> Self {
+		let mut remaining = vec![W::zero(); graph.row_count()];
+		for row_idx in (0..graph.row_count().saturating_sub(1)).rev() {
+			let row_minimum = graph
+				.row(row_idx)
+				.flat_map(|node| graph.edges(node))
+				.filter_map(|edge| {
+					let saturating_add = |cost: W, weight| Some(cost.saturating_add(weight));
+					graph.visit(graph.destination(edge), graph.weight(edge), saturating_add).ok()
+				})
+				.reduce(|a, b| if b < a { b } else { a });
+			// Without edges out of the row there is no path, so any estimate will do.
+			remaining[row_idx] =
+				row_minimum.unwrap_or_else(W::zero).saturating_add(remaining[row_idx + 1]);
+		}
+		RowMinimum { remaining }
+	}
+}
+
+impl<W: Weight> Heuristic<W> for RowMinimum<W> {
+	fn estimate(&self, row: usize, _column: usize) -> W {
+		self.remaining[row]
+	}
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Finds the least cost path from the given column of row 0 to the given
+	/// column of the last row, `None` if there is no such path.
+	///
+	/// Nodes are settled in order of their path cost plus the `heuristic`'s
+	/// estimate of the cost left, so with a good estimate most nodes are never
+	/// reached, unlike with `min_path`'s sweep of every row. A consistent
+	/// heuristic also makes negative weights fine. Reports
+	/// `MinPathError::InvalidColumn` if either column is outside of its row.
+	pub fn a_star(
+		&self,
+		source: usize,
+		target: usize,
+		heuristic: &impl Heuristic<W>,
+	) -> Result<Option<Path<W>>, MinPathError> {
+		if self.row_count() < 2 {
+			return Ok(None);
+		}
+		let last_row = self.row_count() - 1;
+		if source >= self.row(0).len() {
+			return Err(MinPathError::InvalidColumn { row: 0, column: source });
+		}
+		if target >= self.row(last_row).len() {
+			return Err(MinPathError::InvalidColumn { row: last_row, column: target });
+		}
+		let (source, target) = (self.row(0).start + source, self.row(last_row).start + target);
+
+		// `estimates[n]` caches the heuristic's estimate for node `n` once reached.
+		let mut estimates: Vec<Option<W>> = vec![None; self.node_count()];
+		let mut estimate = |node: usize| {
+			*estimates[node].get_or_insert_with(|| {
+				let (row, column) = self.position(node);
+				heuristic.estimate(row, column)
+			})
+		};
+
+		let mut labels: Vec<Option<Label<W>>> = vec![None; self.node_count()];
+		let cost = self.visit(source, W::zero(), W::checked_add)?;
+		labels[source] = Some(Label { cost, predecessor: None });
+		let mut heap = BinaryHeap::new();
+		heap.push(Pending { cost: cost.saturating_add(estimate(source)), node: source });
+
+		while let Some(Pending { cost: priority, node }) = heap.pop() {
+			let cost = match labels[node] {
+				// Skip entries for paths that have since been beaten.
+				Some(label) if label.cost.saturating_add(estimate(node)) == priority => label.cost,
+				_ => continue,
+			};
+			if node == target {
+				return Ok(Some(self.trace(&labels, cost, node)));
+			}
+
+			for edge in self.edges(node) {
+				let dest = self.destination(edge);
+				let cost_to_dest =
+					cost.checked_add(self.weight(edge)).ok_or_else(|| self.overflow(dest))?;
+				let cost_to_dest = self.visit(dest, cost_to_dest, W::checked_add)?;
+				match labels[dest] {
+					Some(label) if label.cost <= cost_to_dest => (),
+					_ => {
+						labels[dest] =
+							Some(Label { cost: cost_to_dest, predecessor: Some((node, edge)) });
+						heap.push(Pending {
+							cost: cost_to_dest.saturating_add(estimate(dest)),
+							node: dest,
+						});
+					}
+				}
+			}
+		}
+
+		Ok(None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cell::Cell;
+
+	use super::*;
+	use crate::layered::example_graph;
+	use crate::Query;
+
+	#[test]
+	fn finds_path_with_heuristics() {
+		let graph = example_graph();
+		let expected = graph.query(&Query::new().source(1).target(0)).unwrap();
+		assert_eq!(graph.a_star(1, 0, &RowMinimum::new(&graph)), Ok(expected.clone()));
+		assert_eq!(graph.a_star(1, 0, &|_, _| 0), Ok(expected));
+		assert_eq!(
+			graph.a_star(2, 0, &|_, _| 0),
+			Err(MinPathError::InvalidColumn { row: 0, column: 2 })
+		);
+	}
+
+	#[test]
+	fn row_minimum_reaches_fewer_nodes() {
+		// Edges lead to the same or a neighbouring column, staying in column 0
+		// costs 1 and all else 2.
+		let columns: usize = 20;
+		let row: Vec<Vec<(usize, u32)>> = (0..columns)
+			.map(|col| {
+				let neighbours = col.saturating_sub(1)..(col + 2).min(columns);
+				neighbours.map(|dest| (dest, if col == 0 && dest == 0 { 1 } else { 2 })).collect()
+			})
+			.collect();
+		let mut rows = vec![row; 19];
+		rows.push(vec![vec![]; columns]);
+		let graph = LayeredGraph::from_rows(&rows).unwrap();
+
+		let reached = Cell::new(0);
+		let counting = |heuristic: &dyn Fn(usize) -> u32| {
+			reached.set(0);
+			let path = graph.a_star(0, 0, &|row, _| {
+				reached.set(reached.get() + 1);
+				heuristic(row)
+			});
+			(path.unwrap().unwrap().cost, reached.get())
+		};
+
+		let row_minimum = RowMinimum::new(&graph);
+		let (cost, with_estimate) = counting(&|row| row_minimum.estimate(row, 0));
+		let (blind_cost, blind) = counting(&|_| 0);
+		assert_eq!((cost, blind_cost), (19, 19));
+		assert!(
+			with_estimate * 3 < blind,
+			"{} nodes reached with estimates, {} without",
+			with_estimate,
+			blind
+		);
+	}
+}
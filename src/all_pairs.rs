@@ -0,0 +1,133 @@
+use crate::{
+	layered::{reached, Label, Objective},
+	LayeredGraph, MinPathError, Path, Weight,
+};
+
+/// Least path costs from every node of row 0 to every node of the last row.
+pub struct CostTable<'a, W> {
+	graph: &'a LayeredGraph<W>,
+	/// `costs[s * target_count + t]` is the cost from column `s` of row 0 to
+	/// column `t` of the last row, `None` if there is no path between them.
+	costs: Vec<Option<W>>,
+	target_count: usize,
+	/// Sweep labels from each source, kept to reconstruct paths.
+	maybe_labels: Option<Vec<Vec<Option<Label<W>>>>>,
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Computes the least path cost from every node of row 0 to every node of
+	/// the last row.
+	///
+	/// Each node of row 0 is swept on its own, which multiplies its row vector
+	/// of costs by each row's (sparse, min-plus) transition matrix in turn, so
+	/// the table takes `O(sources * edges)` time.
+	///
+	/// Paths that overflow are dropped as in `min_path`, the least cost between
+	/// a pair overflowing is reported.
+	pub fn all_pairs(&self) -> Result<CostTable<'_, W>, MinPathError> {
+		self.cost_table(false)
+	}
+
+	/// Same as `all_pairs`, but keeps what is needed to reconstruct the path
+	/// between any pair in `O(rows)` time, at the cost of `O(sources * nodes)`
+	/// memory.
+	pub fn all_pairs_with_paths(&self) -> Result<CostTable<'_, W>, MinPathError> {
+		self.cost_table(true)
+	}
+
+	fn cost_table(&self, keep_labels: bool) -> Result<CostTable<'_, W>, MinPathError> {
+		let target_count = if self.row_count() < 2 { 0 } else { self.last_row().len() };
+		let mut costs = Vec::with_capacity(self.row(0).len() * target_count);
+		let mut maybe_labels =
+			if keep_labels { Some(Vec::with_capacity(self.row(0).len())) } else { None };
+
+		for source in self.row(0) {
+			let relaxed =
+				self.relax(Objective::Minimize, Some((source, W::zero())), W::checked_add)?;
+			for node in self.last_row() {
+				costs.push(reached(&relaxed, node)?.map(|label| label.cost));
+			}
+			if let Some(all_labels) = maybe_labels.as_mut() {
+				all_labels.push(relaxed.0);
+			}
+		}
+
+		Ok(CostTable { graph: self, costs, target_count, maybe_labels })
+	}
+}
+
+impl<'a, W: Weight> CostTable<'a, W> {
+	/// Number of nodes in row 0.
+	pub fn source_count(&self) -> usize {
+		self.graph.row(0).len()
+	}
+
+	/// Number of nodes in the last row, 0 if the graph has a single row.
+	pub fn target_count(&self) -> usize {
+		self.target_count
+	}
+
+	/// Least cost from the given column of row 0 to the given column of the
+	/// last row, `None` if there is no path between them.
+	///
+	/// # Panics
+	///
+	/// Panics if either column is out of bounds.
+	pub fn cost(&self, source: usize, target: usize) -> Option<W> {
+		assert!(source < self.source_count() && target < self.target_count, "column out of bounds");
+		self.costs[source * self.target_count + target]
+	}
+
+	/// Least cost path from the given column of row 0 to the given column of
+	/// the last row, `None` if there is no path between them. Without kept
+	/// paths, see `LayeredGraph::all_pairs_with_paths`, the source is swept
+	/// again.
+	///
+	/// # Panics
+	///
+	/// Panics if either column is out of bounds.
+	pub fn path(&self, source: usize, target: usize) -> Option<Path<W>> {
+		let cost = self.cost(source, target)?;
+		let target = self.graph.last_row().start + target;
+		match self.maybe_labels.as_ref() {
+			Some(all_labels) => Some(self.graph.trace(&all_labels[source], cost, target)),
+			None => {
+				// The sweep already succeeded once, so it can not overflow.
+				let (labels, _) = self
+					.graph
+					.relax(Objective::Minimize, Some((source, W::zero())), W::checked_add)
+					.ok()?;
+				Some(self.graph.trace(&labels, cost, target))
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Query;
+
+	#[test]
+	fn matches_single_pair_queries() {
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, 2), (1, 3)], vec![(0, 0), (1, 1)], vec![(2, 7)]],
+			vec![vec![(0, 6)], vec![(0, 4), (1, 5)], vec![(2, 1)]],
+			vec![vec![], vec![], vec![]],
+		])
+		.unwrap();
+
+		let table = graph.all_pairs().unwrap();
+		let table_with_paths = graph.all_pairs_with_paths().unwrap();
+		for source in 0..3 {
+			for target in 0..3 {
+				let expected = graph.query(&Query::new().source(source).target(target)).unwrap();
+				assert_eq!(table.cost(source, target), expected.as_ref().map(|path| path.cost));
+				assert_eq!(table.path(source, target), expected);
+				assert_eq!(table_with_paths.path(source, target), expected);
+			}
+		}
+		assert_eq!(table.cost(0, 2), None);
+		assert_eq!(table.cost(2, 2), Some(8));
+	}
+}
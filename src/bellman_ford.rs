@@ -0,0 +1,152 @@
+use crate::{layered::Label, Graph, MinPathError, Path, Weight};
+
+impl<W: Weight> Graph<W> {
+	/// Same as `dijkstra`, but weights may be negative and edges may form
+	/// cycles, as long as no cycle reachable from the sources weighs less than
+	/// nothing in total. Such a cycle is reported as a
+	/// `MinPathError::NegativeCycle`, unless going around it overflows first.
+	///
+	/// Every edge is relaxed once per round until a round changes nothing.
+	/// Least cost paths have fewer edges than there are nodes, so a change in
+	/// round `node_count` can only come from a negative cycle.
+	pub fn bellman_ford(
+		&self,
+		sources: &[(usize, usize)],
+		targets: &[(usize, usize)],
+	) -> Result<Option<Path<W>>, MinPathError> {
+		if targets.is_empty() && self.row_count() < 2 {
+			return Ok(None);
+		}
+		let sources = self.nodes_at(sources, 0)?;
+		let targets = self.nodes_at(targets, self.row_count() - 1)?;
+
+		let mut labels: Vec<Option<Label<W>>> = vec![None; self.node_count()];
+		for node in sources {
+			let cost = self.visit(node, W::zero())?;
+			match labels[node] {
+				Some(label) if label.cost <= cost => (),
+				_ => labels[node] = Some(Label { cost, predecessor: None }),
+			}
+		}
+
+		for round in 1..=self.node_count() {
+			let mut last_relaxed = None;
+			for node in 0..self.node_count() {
+				let src_cost = match labels[node] {
+					Some(label) => label.cost,
+					None => continue,
+				};
+				for edge in self.edges(node) {
+					let dest = self.destination(edge);
+					let cost = src_cost
+						.checked_add(self.weight(edge))
+						.ok_or_else(|| self.overflow(dest))?;
+					let cost = self.visit(dest, cost)?;
+					match labels[dest] {
+						Some(label) if label.cost <= cost => (),
+						_ => {
+							labels[dest] = Some(Label { cost, predecessor: Some((node, edge)) });
+							last_relaxed = Some(dest);
+						}
+					}
+				}
+			}
+
+			match last_relaxed {
+				None => break,
+				Some(node) if round == self.node_count() => {
+					let nodes = self
+						.negative_cycle(&labels, node)
+						.into_iter()
+						.map(|node| self.position(node))
+						.collect();
+					return Err(MinPathError::NegativeCycle { nodes });
+				}
+				Some(_) => (),
+			}
+		}
+
+		let mut final_path: Option<(W, usize)> = None;
+		for node in targets {
+			if let Some(label) = labels[node] {
+				match final_path {
+					Some((final_cost, _)) if final_cost <= label.cost => (),
+					_ => final_path = Some((label.cost, node)),
+				}
+			}
+		}
+
+		Ok(final_path.map(|(cost, node)| self.trace(&labels, cost, node)))
+	}
+
+	/// Nodes of the negative cycle `node` was last relaxed through, in the order
+	/// of its edges.
+	fn negative_cycle(&self, labels: &[Option<Label<W>>], mut node: usize) -> Vec<usize> {
+		let predecessor = |node: usize| match labels[node].and_then(|label| label.predecessor) {
+			Some((pred, _)) => pred,
+			None => unreachable!("nodes leading to a negative cycle have a predecessor"),
+		};
+
+		// Going back as many times as there are nodes is bound to end in the cycle.
+		for _ in 0..self.node_count() {
+			node = predecessor(node);
+		}
+		let mut cycle = vec![node];
+		let mut pred = predecessor(node);
+		while pred != node {
+			cycle.push(pred);
+			pred = predecessor(pred);
+		}
+		cycle.reverse();
+		cycle
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn solves_signed_weights() {
+		// The cycle between the nodes of row 1 weighs 0, so it is not worth going around.
+		let graph = Graph::from_rows(&[
+			vec![vec![((1, 0), 4), ((1, 1), 1)]],
+			vec![vec![((1, 1), 2), ((2, 0), 1)], vec![((1, 0), -2), ((2, 0), 5)]],
+			vec![vec![]],
+		])
+		.unwrap();
+		assert!(matches!(graph.dijkstra(&[], &[]), Err(MinPathError::NegativeWeight { .. })));
+		assert_eq!(
+			graph.bellman_ford(&[], &[]),
+			Ok(Some(Path {
+				cost: 0,
+				nodes: vec![(0, 0), (1, 1), (1, 0), (2, 0)],
+				edges: vec![1, 0, 1]
+			}))
+		);
+	}
+
+	#[test]
+	fn reports_negative_cycles() {
+		let graph = Graph::from_rows(&[
+			vec![vec![((1, 0), 1)]],
+			vec![vec![((1, 1), 2), ((2, 0), 1)], vec![((1, 2), -2)], vec![((1, 0), -1)]],
+			vec![vec![]],
+		])
+		.unwrap();
+		match graph.bellman_ford(&[], &[]) {
+			Err(MinPathError::NegativeCycle { mut nodes }) => {
+				// The cycle may be reported from any of its nodes.
+				let start = nodes.iter().position(|&node| node == (1, 0)).unwrap();
+				nodes.rotate_left(start);
+				assert_eq!(nodes, vec![(1, 0), (1, 1), (1, 2)]);
+			}
+			result => panic!("expected a negative cycle, got {:?}", result),
+		}
+		// The cycle can not be reached from row 2.
+		assert_eq!(
+			graph.bellman_ford(&[(2, 0)], &[(2, 0)]).map(|path| path.map(|path| path.cost)),
+			Ok(Some(0))
+		);
+	}
+}
@@ -0,0 +1,180 @@
+use crate::{layered::Label, LayeredGraph, MinPathError, Path, Weight};
+
+/// Edges of a graph grouped by the node they lead to, which the graph itself
+/// does not keep to stay compact. It is built for each search, so other
+/// solvers do not pay for it.
+struct ReverseIndex {
+	/// Edges into node `n` are `incoming[offsets[n]..offsets[n + 1]]`.
+	offsets: Vec<usize>,
+	/// `(source node, edge)` of each edge.
+	incoming: Vec<(usize, usize)>,
+}
+
+impl ReverseIndex {
+	fn new<W: Weight>(graph: &LayeredGraph<W>) -> Self {
+		let mut offsets = vec![0; graph.node_count() + 1];
+		for node in 0..graph.node_count() {
+			for edge in graph.edges(node) {
+				offsets[graph.destination(edge) + 1] += 1;
+			}
+		}
+		for node in 0..graph.node_count() {
+			offsets[node + 1] += offsets[node];
+		}
+
+		// Next free slot of each node's edges.
+		let mut next = offsets.clone();
+		let mut incoming = vec![(0, 0); graph.edge_count()];
+		for node in 0..graph.node_count() {
+			for edge in graph.edges(node) {
+				let dest = graph.destination(edge);
+				incoming[next[dest]] = (node, edge);
+				next[dest] += 1;
+			}
+		}
+		ReverseIndex { offsets, incoming }
+	}
+
+	/// `(source node, edge)` of the edges into the given node.
+	fn incoming(&self, node: usize) -> &[(usize, usize)] {
+		&self.incoming[self.offsets[node]..self.offsets[node + 1]]
+	}
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Finds the least cost path from the given column of row 0 to the given
+	/// column of the last row, `None` if there is no such path.
+	///
+	/// Paths are swept forward from the source down to the middle row, and
+	/// backward, through the edges into each node, from the target up to it.
+	/// Each sweep only goes through half the rows and only through nodes its
+	/// end can reach, before the best meeting point in the middle row is
+	/// picked. Reports `MinPathError::InvalidColumn` if either column is
+	/// outside of its row.
+	pub fn bidirectional_path(
+		&self,
+		source: usize,
+		target: usize,
+	) -> Result<Option<Path<W>>, MinPathError> {
+		if self.row_count() < 2 {
+			return Ok(None);
+		}
+		let last_row = self.row_count() - 1;
+		if source >= self.row(0).len() {
+			return Err(MinPathError::InvalidColumn { row: 0, column: source });
+		}
+		if target >= self.row(last_row).len() {
+			return Err(MinPathError::InvalidColumn { row: last_row, column: target });
+		}
+		let (source, target) = (self.row(0).start + source, self.row(last_row).start + target);
+		let middle_row = self.row(last_row / 2);
+
+		// `forward[n]` is the best path from the source to node `n`, including
+		// its weight.
+		let mut forward: Vec<Option<Label<W>>> = vec![None; self.node_count()];
+		forward[source] =
+			Some(Label { cost: self.visit(source, W::zero(), W::checked_add)?, predecessor: None });
+		for node in source..middle_row.start {
+			let src_cost = match forward[node] {
+				Some(label) => label.cost,
+				None => continue,
+			};
+			for edge in self.edges(node) {
+				let dest = self.destination(edge);
+				let cost =
+					src_cost.checked_add(self.weight(edge)).ok_or_else(|| self.overflow(dest))?;
+				let cost = self.visit(dest, cost, W::checked_add)?;
+				match forward[dest] {
+					Some(label) if label.cost <= cost => (),
+					_ => forward[dest] = Some(Label { cost, predecessor: Some((node, edge)) }),
+				}
+			}
+		}
+
+		// `backward[n]` is the best path from node `n` to the target, excluding
+		// the weight of `n`, with the next node and the edge taken to it in
+		// place of a predecessor.
+		let reverse_index = ReverseIndex::new(self);
+		let mut backward: Vec<Option<Label<W>>> = vec![None; self.node_count()];
+		backward[target] = Some(Label { cost: W::zero(), predecessor: None });
+		for node in (middle_row.end..=target).rev() {
+			let dest_cost = match backward[node] {
+				Some(label) => self.visit(node, label.cost, W::checked_add)?,
+				None => continue,
+			};
+			for &(src, edge) in reverse_index.incoming(node) {
+				let cost =
+					dest_cost.checked_add(self.weight(edge)).ok_or_else(|| self.overflow(src))?;
+				match backward[src] {
+					Some(label) if label.cost <= cost => (),
+					_ => backward[src] = Some(Label { cost, predecessor: Some((node, edge)) }),
+				}
+			}
+		}
+
+		let mut meeting: Option<(W, usize)> = None;
+		for node in middle_row {
+			if let (Some(to), Some(from)) = (forward[node], backward[node]) {
+				let cost = to.cost.checked_add(from.cost).ok_or_else(|| self.overflow(target))?;
+				match meeting {
+					Some((meeting_cost, _)) if meeting_cost <= cost => (),
+					_ => meeting = Some((cost, node)),
+				}
+			}
+		}
+
+		Ok(meeting.map(|(cost, mut node)| {
+			let mut path = self.trace(&forward, cost, node);
+			while let Some((next, edge)) = backward[node].and_then(|label| label.predecessor) {
+				path.nodes.push(self.position(next));
+				path.edges.push(edge - self.edges(node).start);
+				node = next;
+			}
+			path
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::layered::example_graph;
+	use crate::Query;
+
+	#[test]
+	fn meets_in_the_middle() {
+		let graph = example_graph();
+		for (source, target) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+			let expected = graph.query(&Query::new().source(source).target(target));
+			assert_eq!(graph.bidirectional_path(source, target), expected);
+		}
+		assert_eq!(
+			graph.bidirectional_path(0, 2),
+			Err(MinPathError::InvalidColumn { row: 2, column: 2 })
+		);
+	}
+
+	#[test]
+	fn meets_in_longer_graphs() {
+		// Column `c` leads to columns `c` and `c + 1` mod 3, with weights depending on both.
+		let row: Vec<Vec<(usize, i32)>> = (0..3)
+			.map(|col| vec![(col, col as i32 - 1), ((col + 1) % 3, 2 - col as i32)])
+			.collect();
+		let mut rows = vec![row; 6];
+		rows.push(vec![vec![]; 3]);
+		let mut graph = LayeredGraph::from_rows(&rows).unwrap();
+		graph.set_node_weight(3, 1, 5).unwrap();
+		graph.set_node_weight(6, 2, -3).unwrap();
+
+		for source in 0..3 {
+			for target in 0..3 {
+				let expected = graph.query(&Query::new().source(source).target(target)).unwrap();
+				let path = graph.bidirectional_path(source, target).unwrap();
+				assert_eq!(path.as_ref().map(|path| path.cost), expected.map(|path| path.cost));
+				let path = path.unwrap();
+				assert_eq!((path.nodes.len(), path.edges.len()), (7, 6));
+				assert_eq!((path.nodes[0], path.nodes[6]), ((0, source), (6, target)));
+			}
+		}
+	}
+}
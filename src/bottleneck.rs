@@ -0,0 +1,68 @@
+use crate::{Extended, LayeredGraph, MaxMin, MinMax, Path, Selective, Weight};
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Finds the path from row 0 to the last row whose heaviest edge is as light
+	/// as possible, `None` if there is no path. The path's cost is the weight of
+	/// that heaviest edge, node weights are not taken into account.
+	pub fn minimax_path(&self) -> Option<Path<W>> {
+		self.bottleneck_path(|weight| MinMax(Extended::Finite(weight)), |value| value.0)
+	}
+
+	/// Finds the path from row 0 to the last row whose lightest edge is as heavy
+	/// as possible, e.g. the widest path when weights are capacities, `None` if
+	/// there is no path. The path's cost is the weight of that lightest edge,
+	/// node weights are not taken into account.
+	pub fn maximin_path(&self) -> Option<Path<W>> {
+		self.bottleneck_path(|weight| MaxMin(Extended::Finite(weight)), |value| value.0)
+	}
+
+	fn bottleneck_path<S: Selective>(
+		&self,
+		value: impl Fn(W) -> S,
+		extended: impl Fn(S) -> Extended<W>,
+	) -> Option<Path<W>> {
+		let path = self.edge_semiring_path(value)?;
+		// Paths have at least one edge, so their bottleneck is one of their weights.
+		let cost = extended(path.cost).finite()?;
+		Some(Path { cost, nodes: path.nodes, edges: path.edges })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn finds_bottleneck_paths() {
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, 5), (1, 9)], vec![(1, 2)]],
+			vec![vec![(0, 8)], vec![(0, 1), (1, 7)]],
+			vec![vec![], vec![]],
+		])
+		.unwrap();
+
+		assert_eq!(
+			graph.minimax_path(),
+			Some(Path { cost: 2, nodes: vec![(0, 1), (1, 1), (2, 0)], edges: vec![0, 0] })
+		);
+		assert_eq!(
+			graph.maximin_path(),
+			Some(Path { cost: 7, nodes: vec![(0, 0), (1, 1), (2, 1)], edges: vec![1, 1] })
+		);
+		assert_eq!(
+			LayeredGraph::<usize>::from_rows(&[vec![vec![]], vec![vec![]]]).unwrap().maximin_path(),
+			None
+		);
+	}
+
+	#[test]
+	fn ignores_node_weights() {
+		let mut graph = LayeredGraph::from_rows(&[vec![vec![(0, 1)]], vec![vec![]]]).unwrap();
+		graph.set_node_weight(1, 0, 100).unwrap();
+		assert_eq!(
+			graph.minimax_path(),
+			Some(Path { cost: 1, nodes: vec![(0, 0), (1, 0)], edges: vec![0] })
+		);
+		assert_eq!(graph.maximin_path().map(|path| path.cost), Some(1));
+	}
+}
@@ -0,0 +1,233 @@
+use crate::{LayeredGraph, MinPathError, Path, Weight};
+
+/// A path to a node that is not dominated by any other one to the same node.
+#[derive(Clone)]
+pub(crate) struct ResourceLabel<W> {
+	/// Node the path leads to.
+	pub(crate) node: usize,
+	/// Weight of the path.
+	pub(crate) cost: W,
+	/// Resources consumed along the path.
+	pub(crate) resources: Vec<W>,
+	/// Label of the path to the previous node and the edge taken from it,
+	/// `None` for nodes in row 0.
+	predecessor: Option<(usize, usize)>,
+}
+
+/// All the labels created by a sweep, and indices into them of the ones kept
+/// for each node.
+pub(crate) type SweptLabels<W> = (Vec<ResourceLabel<W>>, Vec<Vec<usize>>);
+
+impl<W: Weight> ResourceLabel<W> {
+	/// Cost and resources of the path, as compared by `dominates`.
+	fn key(&self) -> (W, &[W]) {
+		(self.cost, &self.resources)
+	}
+}
+
+/// Whether a path costing `cost` and consuming `resources` is at least as good,
+/// up to `epsilon`, as one costing `other_cost` and consuming
+/// `other_resources`, which can then be dropped. `epsilon[0]` is the slack on
+/// the cost and `epsilon[1..]` the slack on each resource, missing ones are 0.
+pub(crate) fn dominates<W: Weight>(
+	(cost, resources): (W, &[W]),
+	(other_cost, other_resources): (W, &[W]),
+	epsilon: &[W],
+) -> bool {
+	let slack = |idx: usize| epsilon.get(idx).copied().unwrap_or_else(W::zero);
+	cost <= other_cost.saturating_add(slack(0))
+		&& resources
+			.iter()
+			.zip(other_resources)
+			.enumerate()
+			.all(|(idx, (&own, &other))| own <= other.saturating_add(slack(idx + 1)))
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Finds the least cost path from row 0 to the last row whose consumption
+	/// of each resource stays within `budget`, `None` if there is no such path.
+	/// Resources past the end of `budget` are not constrained.
+	///
+	/// Every node keeps the paths to it that no other path beats on both cost
+	/// and all resources, so only those are extended to the next row. Pruning
+	/// paths over budget is only sound if resources never decrease, so
+	/// `MinPathError::NegativeResource` is reported for the first edge
+	/// consuming a negative amount.
+	pub fn constrained_path(&self, budget: &[W]) -> Result<Option<Path<W>>, MinPathError> {
+		for node in 0..self.node_count() {
+			for edge in self.edges(node) {
+				if self.resources(edge).iter().any(|&resource| resource < W::zero()) {
+					let (row, column) = self.position(node);
+					return Err(MinPathError::NegativeResource {
+						row,
+						column,
+						edge: edge - self.edges(node).start,
+					});
+				}
+			}
+		}
+
+		let within_budget = |resources: &[W]| {
+			resources.iter().zip(budget).all(|(used, available)| used <= available)
+		};
+		let (arena, labels) = self.resource_sweep(within_budget)?;
+
+		let mut final_label: Option<usize> = None;
+		for node in self.last_row() {
+			for &label_idx in labels[node].iter() {
+				match final_label {
+					Some(final_idx) if arena[final_idx].cost <= arena[label_idx].cost => (),
+					_ => final_label = Some(label_idx),
+				}
+			}
+		}
+
+		Ok(final_label.map(|label_idx| self.trace_resource_label(&arena, label_idx)))
+	}
+
+	/// Sweeps the rows keeping, for each node, the paths to it that are
+	/// `feasible` and not dominated by another one. Returns all the labels
+	/// created and the indices of the ones kept for each node of the last row;
+	/// other nodes' lists are emptied once extended.
+	pub(crate) fn resource_sweep(
+		&self,
+		feasible: impl Fn(&[W]) -> bool,
+	) -> Result<SweptLabels<W>, MinPathError> {
+		let mut arena: Vec<ResourceLabel<W>> = Vec::new();
+		let mut labels: Vec<Vec<usize>> = vec![Vec::new(); self.node_count()];
+		if self.row_count() < 2 {
+			return Ok((arena, labels));
+		}
+
+		for node in self.row(0) {
+			let cost = self.visit(node, W::zero(), W::checked_add)?;
+			labels[node].push(arena.len());
+			arena.push(ResourceLabel {
+				node,
+				cost,
+				resources: vec![W::zero(); self.resource_count()],
+				predecessor: None,
+			});
+		}
+
+		for node in 0..self.last_row().start {
+			for label_idx in std::mem::take(&mut labels[node]) {
+				for edge in self.edges(node) {
+					let dest = self.destination(edge);
+					let label = &arena[label_idx];
+					let cost = label
+						.cost
+						.checked_add(self.weight(edge))
+						.ok_or_else(|| self.overflow(dest))?;
+					let cost = self.visit(dest, cost, W::checked_add)?;
+					let resources = label
+						.resources
+						.iter()
+						.zip(self.resources(edge))
+						.map(|(&used, &consumed)| {
+							used.checked_add(consumed).ok_or_else(|| self.overflow(dest))
+						})
+						.collect::<Result<Vec<W>, MinPathError>>()?;
+					if !feasible(&resources) {
+						continue;
+					}
+
+					let label = ResourceLabel {
+						node: dest,
+						cost,
+						resources,
+						predecessor: Some((label_idx, edge)),
+					};
+					insert_label(&mut arena, &mut labels[dest], label, &[]);
+				}
+			}
+		}
+
+		Ok((arena, labels))
+	}
+
+	/// Follows predecessors back from the given label to row 0.
+	pub(crate) fn trace_resource_label(
+		&self,
+		arena: &[ResourceLabel<W>],
+		mut label_idx: usize,
+	) -> Path<W> {
+		let cost = arena[label_idx].cost;
+		let mut nodes = vec![self.position(arena[label_idx].node)];
+		let mut edges = Vec::new();
+		while let Some((pred_idx, edge)) = arena[label_idx].predecessor {
+			let pred = arena[pred_idx].node;
+			nodes.push(self.position(pred));
+			edges.push(edge - self.edges(pred).start);
+			label_idx = pred_idx;
+		}
+		nodes.reverse();
+		edges.reverse();
+
+		Path { cost, nodes, edges }
+	}
+}
+
+/// Adds `label` to `arena` and to the `kept` ones unless one of those dominates
+/// it up to `epsilon`, dropping the kept ones it strictly dominates.
+pub(crate) fn insert_label<W: Weight>(
+	arena: &mut Vec<ResourceLabel<W>>,
+	kept: &mut Vec<usize>,
+	label: ResourceLabel<W>,
+	epsilon: &[W],
+) {
+	let key = (label.cost, label.resources.as_slice());
+	if kept.iter().any(|&other| dominates(arena[other].key(), key, epsilon)) {
+		return;
+	}
+	kept.retain(|&other| !dominates(key, arena[other].key(), &[]));
+	kept.push(arena.len());
+	arena.push(label);
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{cell::RefCell, convert::TryFrom, rc::Rc};
+
+	use super::*;
+	use crate::{Edge, Node};
+
+	#[test]
+	fn finds_path_within_budget() {
+		let r2c0 = Rc::new(RefCell::new(Node::new(vec![])));
+		let r1c0 =
+			Rc::new(RefCell::new(Node::new(vec![Edge::with_resources(1, vec![5], r2c0.clone())])));
+		let r1c1 =
+			Rc::new(RefCell::new(Node::new(vec![Edge::with_resources(2, vec![1], r2c0.clone())])));
+		let r0c0 = Rc::new(RefCell::new(Node::new(vec![
+			Edge::with_resources(1, vec![5], r1c0.clone()),
+			Edge::with_resources(3, vec![1], r1c1.clone()),
+		])));
+		let graph =
+			LayeredGraph::try_from(&vec![vec![r0c0], vec![r1c0, r1c1], vec![r2c0]]).unwrap();
+
+		assert_eq!(graph.constrained_path(&[10]).unwrap().map(|path| path.cost), Some(2));
+		assert_eq!(
+			graph.constrained_path(&[9]),
+			Ok(Some(Path { cost: 5, nodes: vec![(0, 0), (1, 1), (2, 0)], edges: vec![1, 0] }))
+		);
+		assert_eq!(graph.constrained_path(&[1]), Ok(None));
+		assert_eq!(graph.constrained_path(&[]), graph.min_path());
+	}
+
+	#[test]
+	fn rejects_negative_resources() {
+		let r1c0 = Rc::new(RefCell::new(Node::new(vec![])));
+		let r0c0 = Rc::new(RefCell::new(Node::new(vec![Edge::with_resources(
+			1,
+			vec![0, -1],
+			r1c0.clone(),
+		)])));
+		let graph = LayeredGraph::try_from(&vec![vec![r0c0], vec![r1c0]]).unwrap();
+		assert_eq!(graph.resource_count(), 2);
+		assert_eq!(
+			graph.constrained_path(&[1, 1]),
+			Err(MinPathError::NegativeResource { row: 0, column: 0, edge: 0 })
+		);
+	}
+}
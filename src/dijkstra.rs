@@ -0,0 +1,172 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::{layered::Label, Graph, MinPathError, Path, Weight};
+
+/// Node waiting to be settled, ordered so the cheapest one is on top of a
+/// `BinaryHeap`.
+pub(crate) struct Pending<W> {
+	pub(crate) cost: W,
+	pub(crate) node: usize,
+}
+
+impl<W: Weight> PartialEq for Pending<W> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl<W: Weight> Eq for Pending<W> {}
+
+impl<W: Weight> PartialOrd for Pending<W> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<W: Weight> Ord for Pending<W> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		// Reversed, `BinaryHeap` is a max heap. Ties go to the lowest node.
+		let by_cost = other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal);
+		by_cost.then_with(|| other.node.cmp(&self.node))
+	}
+}
+
+impl<W: Weight> Graph<W> {
+	/// Finds the least cost path from the `(row, column)` of any of the
+	/// `sources` to that of any of the `targets`, `None` if there is no such
+	/// path. Empty `sources` stand for all of row 0 and empty `targets` for all
+	/// of the last row, so `dijkstra(&[], &[])` solves the same problem as
+	/// `min_path`, but edges may form cycles.
+	///
+	/// Nodes are settled cheapest first, which stops as soon as a target is,
+	/// and is only correct if no edge or node weight is negative: reports
+	/// `MinPathError::NegativeWeight` for the first negative edge and
+	/// `MinPathError::InvalidCost` for the first negative node.
+	pub fn dijkstra(
+		&self,
+		sources: &[(usize, usize)],
+		targets: &[(usize, usize)],
+	) -> Result<Option<Path<W>>, MinPathError> {
+		for node in 0..self.node_count() {
+			if self.node_weight(node).is_some_and(|weight| weight < W::zero()) {
+				let (row, column) = self.position(node);
+				return Err(MinPathError::InvalidCost { row, column });
+			}
+			for edge in self.edges(node) {
+				if self.weight(edge) < W::zero() {
+					let (row, column) = self.position(node);
+					return Err(MinPathError::NegativeWeight {
+						row,
+						column,
+						edge: edge - self.edges(node).start,
+					});
+				}
+			}
+		}
+		if targets.is_empty() && self.row_count() < 2 {
+			return Ok(None);
+		}
+		let sources = self.nodes_at(sources, 0)?;
+		let mut is_target = vec![false; self.node_count()];
+		for target in self.nodes_at(targets, self.row_count() - 1)? {
+			is_target[target] = true;
+		}
+
+		let mut labels: Vec<Option<Label<W>>> = vec![None; self.node_count()];
+		let mut settled = vec![false; self.node_count()];
+		let mut heap = BinaryHeap::new();
+		for node in sources {
+			let cost = self.visit(node, W::zero())?;
+			match labels[node] {
+				Some(label) if label.cost <= cost => (),
+				_ => {
+					labels[node] = Some(Label { cost, predecessor: None });
+					heap.push(Pending { cost, node });
+				}
+			}
+		}
+
+		while let Some(Pending { cost, node }) = heap.pop() {
+			if settled[node] {
+				// A cheaper path to the node was already settled.
+				continue;
+			}
+			settled[node] = true;
+			if is_target[node] {
+				// Every target left costs at least as much.
+				return Ok(Some(self.trace(&labels, cost, node)));
+			}
+
+			for edge in self.edges(node) {
+				let dest = self.destination(edge);
+				if settled[dest] {
+					continue;
+				}
+				let cost_to_dest =
+					cost.checked_add(self.weight(edge)).ok_or_else(|| self.overflow(dest))?;
+				let cost_to_dest = self.visit(dest, cost_to_dest)?;
+				match labels[dest] {
+					Some(label) if label.cost <= cost_to_dest => (),
+					_ => {
+						labels[dest] =
+							Some(Label { cost: cost_to_dest, predecessor: Some((node, edge)) });
+						heap.push(Pending { cost: cost_to_dest, node: dest });
+					}
+				}
+			}
+		}
+
+		Ok(None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{cell::RefCell, convert::TryFrom, rc::Rc};
+
+	use super::*;
+	use crate::{Edge, Node};
+
+	#[test]
+	fn solves_cyclic_graphs() {
+		// Row 1 has a cycle between its nodes, and an edge back to row 0.
+		let graph = Graph::from_rows(&[
+			vec![vec![((1, 0), 1)], vec![((1, 1), 10)]],
+			vec![vec![((1, 1), 1), ((0, 1), 1)], vec![((1, 0), 1), ((2, 0), 1)]],
+			vec![vec![]],
+		])
+		.unwrap();
+		assert!(matches!(graph.min_path(), Err(MinPathError::Cycle { .. })));
+		assert_eq!(
+			graph.dijkstra(&[], &[]),
+			Ok(Some(Path {
+				cost: 3,
+				nodes: vec![(0, 0), (1, 0), (1, 1), (2, 0)],
+				edges: vec![0, 0, 1]
+			}))
+		);
+		assert_eq!(
+			graph.dijkstra(&[(1, 1)], &[(0, 1)]).map(|path| path.map(|path| path.cost)),
+			Ok(Some(2))
+		);
+		assert_eq!(graph.dijkstra(&[(1, 0)], &[(0, 0)]), Ok(None));
+		assert_eq!(
+			graph.dijkstra(&[(3, 0)], &[]),
+			Err(MinPathError::InvalidColumn { row: 3, column: 0 })
+		);
+	}
+
+	#[test]
+	fn rejects_negative_weights() {
+		let r1c0 = Rc::new(RefCell::new(Node::new(vec![])));
+		let r0c0 = Rc::new(RefCell::new(Node::new(vec![
+			Edge::new(1, r1c0.clone()),
+			Edge::new(-1, r1c0.clone()),
+		])));
+		let graph = Graph::try_from(&vec![vec![r0c0], vec![r1c0]]).unwrap();
+		assert_eq!(
+			graph.dijkstra(&[], &[]),
+			Err(MinPathError::NegativeWeight { row: 0, column: 0, edge: 1 })
+		);
+	}
+}
@@ -0,0 +1,116 @@
+use std::{error::Error, fmt};
+
+/// Reasons an input does not describe a valid min path cost problem.
+///
+/// Nodes are located by their `row` and `column` in the input, and edges by
+/// their index into `Node::edges` of the node they leave.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MinPathError {
+	/// The input has no rows.
+	EmptyInput,
+	/// A row of the input does not have as many nodes as the input has rows.
+	NotSquare { row: usize, len: usize, expected: usize },
+	/// An edge leads to a node that is not in the row right after its source.
+	NonAdjacentEdge { row: usize, column: usize, edge: usize, destination: (usize, usize) },
+	/// An edge leads to a node that is not part of the input. From
+	/// `TransitionMatrix::from_nodes`, `row` is 0 and `column` indexes `from`.
+	DanglingDestination { row: usize, column: usize, edge: usize },
+	/// Edges between the given nodes, in order, lead back to the first one.
+	Cycle { nodes: Vec<(usize, usize)> },
+	/// Edges between the given nodes, in order, lead back to the first one and
+	/// weigh less than nothing in total, so paths through them have no least
+	/// cost.
+	NegativeCycle { nodes: Vec<(usize, usize)> },
+	/// An edge has a weight, or resource, that can not be compared, e.g. NaN.
+	/// From `TransitionMatrix::from_nodes`, `row` is 0 and `column` indexes
+	/// `from`.
+	InvalidWeight { row: usize, column: usize, edge: usize },
+	/// An edge's weight is not a probability between 0 and 1.
+	InvalidProbability { row: usize, column: usize, edge: usize },
+	/// An edge has a negative weight, which the solver does not support.
+	NegativeWeight { row: usize, column: usize, edge: usize },
+	/// An edge consumes a negative amount of a resource.
+	NegativeResource { row: usize, column: usize, edge: usize },
+	/// A query refers to a column outside of the row.
+	InvalidColumn { row: usize, column: usize },
+	/// The given node's weight, or a query's initial or terminal cost for it,
+	/// can not be compared, e.g. NaN.
+	InvalidCost { row: usize, column: usize },
+	/// The cost of a path to the given node does not fit in its type.
+	Overflow { row: usize, column: usize },
+	/// The cost of the given entry of a `TransitionMatrix` product does not
+	/// fit in its type. Unlike other variants, `row` and `column` index the
+	/// product rather than the input.
+	EntryOverflow { row: usize, column: usize },
+}
+
+impl fmt::Display for MinPathError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			MinPathError::EmptyInput => write!(f, "input has no rows"),
+			MinPathError::NotSquare { row, len, expected } => {
+				write!(f, "row {} has {} nodes, expected {}", row, len, expected)
+			}
+			MinPathError::NonAdjacentEdge { row, column, edge, destination } => write!(
+				f,
+				"edge {} of node ({}, {}) leads to node ({}, {}) outside of the next row",
+				edge, row, column, destination.0, destination.1
+			),
+			MinPathError::DanglingDestination { row, column, edge } => {
+				write!(
+					f,
+					"edge {} of node ({}, {}) leads to a node outside of the input",
+					edge, row, column
+				)
+			}
+			MinPathError::Cycle { nodes } => {
+				write!(f, "cycle through nodes")?;
+				for (row, column) in nodes.iter() {
+					write!(f, " ({}, {})", row, column)?;
+				}
+				Ok(())
+			}
+			MinPathError::NegativeCycle { nodes } => {
+				write!(f, "negative cycle through nodes")?;
+				for (row, column) in nodes.iter() {
+					write!(f, " ({}, {})", row, column)?;
+				}
+				Ok(())
+			}
+			MinPathError::InvalidWeight { row, column, edge } => {
+				write!(f, "edge {} of node ({}, {}) has an invalid weight", edge, row, column)
+			}
+			MinPathError::InvalidProbability { row, column, edge } => {
+				write!(
+					f,
+					"edge {} of node ({}, {}) has a weight outside of [0, 1]",
+					edge, row, column
+				)
+			}
+			MinPathError::NegativeWeight { row, column, edge } => {
+				write!(f, "edge {} of node ({}, {}) has a negative weight", edge, row, column)
+			}
+			MinPathError::NegativeResource { row, column, edge } => {
+				write!(
+					f,
+					"edge {} of node ({}, {}) consumes a negative resource",
+					edge, row, column
+				)
+			}
+			MinPathError::InvalidColumn { row, column } => {
+				write!(f, "row {} has no column {}", row, column)
+			}
+			MinPathError::InvalidCost { row, column } => {
+				write!(f, "node ({}, {}) has an invalid weight or cost", row, column)
+			}
+			MinPathError::Overflow { row, column } => {
+				write!(f, "cost of a path to node ({}, {}) overflows", row, column)
+			}
+			MinPathError::EntryOverflow { row, column } => {
+				write!(f, "cost of entry ({}, {}) of a matrix product overflows", row, column)
+			}
+		}
+	}
+}
+
+impl Error for MinPathError {}
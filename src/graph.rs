@@ -0,0 +1,387 @@
+use std::{cell::RefCell, collections::HashMap, convert::TryFrom, ops::Range, rc::Rc};
+
+use crate::{layered::Label, Input, MinPathError, Node, Path, Weight};
+
+/// Compact graph whose edges may lead from any node to any other one, e.g.
+/// skipping rows, unlike those of a `LayeredGraph`.
+///
+/// Nodes are still grouped in rows, paths go from any node of row 0 to any
+/// node of the last row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Graph<W = usize> {
+	/// Nodes of row `r` are `row_offsets[r]..row_offsets[r + 1]`.
+	row_offsets: Vec<usize>,
+	/// Edges out of node `n` are `edge_offsets[n]..edge_offsets[n + 1]`.
+	edge_offsets: Vec<usize>,
+	/// Node each edge leads to.
+	destinations: Vec<usize>,
+	/// Weight of each edge.
+	weights: Vec<W>,
+	/// Cost of visiting each node, if it has one.
+	node_weights: Vec<Option<W>>,
+}
+
+impl<W: Weight> Graph<W> {
+	/// Builds a graph from its adjacency lists: `rows[r][c]` holds the
+	/// `((destination row, destination column), weight)` of each edge out of
+	/// the node in row `r`, column `c`.
+	#[allow(clippy::type_complexity)]
+	pub fn from_rows(rows: &[Vec<Vec<((usize, usize), W)>>]) -> Result<Self, MinPathError> {
+		if rows.is_empty() {
+			return Err(MinPathError::EmptyInput);
+		}
+
+		let mut graph = Graph::with_row_lens(rows.iter().map(Vec::len));
+		for (row_idx, row) in rows.iter().enumerate() {
+			for (col_idx, edges) in row.iter().enumerate() {
+				for (edge_idx, &((dest_row_idx, dest_col_idx), weight)) in edges.iter().enumerate()
+				{
+					if dest_row_idx >= rows.len() || dest_col_idx >= rows[dest_row_idx].len() {
+						return Err(MinPathError::DanglingDestination {
+							row: row_idx,
+							column: col_idx,
+							edge: edge_idx,
+						});
+					}
+					if !weight.is_valid() {
+						return Err(MinPathError::InvalidWeight {
+							row: row_idx,
+							column: col_idx,
+							edge: edge_idx,
+						});
+					}
+					graph.destinations.push(graph.row_offsets[dest_row_idx] + dest_col_idx);
+					graph.weights.push(weight);
+				}
+				graph.edge_offsets.push(graph.destinations.len());
+				graph.node_weights.push(None);
+			}
+		}
+
+		Ok(graph)
+	}
+
+	fn with_row_lens(row_lens: impl Iterator<Item = usize>) -> Self {
+		let mut row_offsets = vec![0];
+		for row_len in row_lens {
+			row_offsets.push(row_offsets[row_offsets.len() - 1] + row_len);
+		}
+		Graph {
+			row_offsets,
+			edge_offsets: vec![0],
+			destinations: Vec::new(),
+			weights: Vec::new(),
+			node_weights: Vec::new(),
+		}
+	}
+
+	/// Number of rows.
+	pub fn row_count(&self) -> usize {
+		self.row_offsets.len() - 1
+	}
+
+	/// Total number of nodes.
+	pub fn node_count(&self) -> usize {
+		self.edge_offsets.len() - 1
+	}
+
+	/// Total number of edges.
+	pub fn edge_count(&self) -> usize {
+		self.destinations.len()
+	}
+
+	/// Nodes of the given row.
+	pub(crate) fn row(&self, row_idx: usize) -> Range<usize> {
+		self.row_offsets[row_idx]..self.row_offsets[row_idx + 1]
+	}
+
+	/// Edges out of the given node.
+	pub(crate) fn edges(&self, node: usize) -> Range<usize> {
+		self.edge_offsets[node]..self.edge_offsets[node + 1]
+	}
+
+	/// Node the given edge leads to.
+	pub(crate) fn destination(&self, edge: usize) -> usize {
+		self.destinations[edge]
+	}
+
+	/// Weight of the given edge.
+	pub(crate) fn weight(&self, edge: usize) -> W {
+		self.weights[edge]
+	}
+
+	/// Cost of visiting the given node, if it has one.
+	pub(crate) fn node_weight(&self, node: usize) -> Option<W> {
+		self.node_weights[node]
+	}
+
+	/// `(row, column)` of the given node.
+	pub(crate) fn position(&self, node: usize) -> (usize, usize) {
+		let row_idx = self.row_offsets.partition_point(|&offset| offset <= node) - 1;
+		(row_idx, node - self.row_offsets[row_idx])
+	}
+
+	/// Nodes at the given `(row, column)` positions, or those of `default_row` if
+	/// there are none.
+	pub(crate) fn nodes_at(
+		&self,
+		positions: &[(usize, usize)],
+		default_row: usize,
+	) -> Result<Vec<usize>, MinPathError> {
+		if positions.is_empty() {
+			return Ok(self.row(default_row).collect());
+		}
+
+		positions
+			.iter()
+			.map(|&(row, column)| {
+				if row >= self.row_count() || column >= self.row(row).len() {
+					Err(MinPathError::InvalidColumn { row, column })
+				} else {
+					Ok(self.row_offsets[row] + column)
+				}
+			})
+			.collect()
+	}
+
+	/// Adds the cost of visiting `node`, if any, to the cost of a path reaching it.
+	pub(crate) fn visit(&self, node: usize, cost: W) -> Result<W, MinPathError> {
+		match self.node_weights[node] {
+			Some(weight) => cost.checked_add(weight).ok_or_else(|| self.overflow(node)),
+			None => Ok(cost),
+		}
+	}
+
+	/// Error for a path to the given node whose cost overflows.
+	pub(crate) fn overflow(&self, node: usize) -> MinPathError {
+		let (row, column) = self.position(node);
+		MinPathError::Overflow { row, column }
+	}
+
+	/// Finds the least cost path from row 0 to the last row, `None` if there is
+	/// no such path. Like `LayeredGraph::min_path`, a graph with fewer than two
+	/// rows has no path.
+	///
+	/// Nodes are swept in topological order, so that every path to a node is
+	/// known before leaving it. Reports `MinPathError::Cycle` if there is no
+	/// such order, and `MinPathError::Overflow` at the first node whose path
+	/// cost does not fit in a `W`.
+	pub fn min_path(&self) -> Result<Option<Path<W>>, MinPathError> {
+		let order = topological_order(&self.edge_offsets, &self.destinations).map_err(|cycle| {
+			MinPathError::Cycle {
+				nodes: cycle.into_iter().map(|node| self.position(node)).collect(),
+			}
+		})?;
+		if self.row_count() < 2 {
+			return Ok(None);
+		}
+
+		let mut labels: Vec<Option<Label<W>>> = vec![None; self.node_count()];
+		for node in self.row(0) {
+			labels[node] = Some(Label { cost: self.visit(node, W::zero())?, predecessor: None });
+		}
+		for node in order {
+			let src_cost = match labels[node] {
+				Some(label) => label.cost,
+				None => continue,
+			};
+
+			for edge in self.edges(node) {
+				let dest = self.destinations[edge];
+				let cost =
+					src_cost.checked_add(self.weights[edge]).ok_or_else(|| self.overflow(dest))?;
+				let cost = self.visit(dest, cost)?;
+				match labels[dest] {
+					Some(label) if label.cost <= cost => (),
+					_ => labels[dest] = Some(Label { cost, predecessor: Some((node, edge)) }),
+				}
+			}
+		}
+
+		let mut final_path: Option<(W, usize)> = None;
+		for node in self.row(self.row_count() - 1) {
+			if let Some(label) = labels[node] {
+				match final_path {
+					Some((final_cost, _)) if final_cost <= label.cost => (),
+					_ => final_path = Some((label.cost, node)),
+				}
+			}
+		}
+
+		Ok(final_path.map(|(cost, node)| self.trace(&labels, cost, node)))
+	}
+
+	/// Follows predecessors back from `node` to the start of its path.
+	pub(crate) fn trace(&self, labels: &[Option<Label<W>>], cost: W, mut node: usize) -> Path<W> {
+		let mut nodes = vec![self.position(node)];
+		let mut edges = Vec::new();
+		while let Some((pred, edge)) = labels[node].and_then(|label| label.predecessor) {
+			nodes.push(self.position(pred));
+			edges.push(edge - self.edge_offsets[pred]);
+			node = pred;
+		}
+		nodes.reverse();
+		edges.reverse();
+
+		Path { cost, nodes, edges }
+	}
+}
+
+impl<W: Weight> TryFrom<&Input<W>> for Graph<W> {
+	type Error = MinPathError;
+
+	/// Converts the input, checking that it is non-empty and that every edge
+	/// leads to a node of the input. Edges may form cycles, which solvers that
+	/// need a topological order report.
+	fn try_from(input: &Input<W>) -> Result<Self, Self::Error> {
+		if input.is_empty() {
+			return Err(MinPathError::EmptyInput);
+		}
+
+		let mut graph = Graph::with_row_lens(input.iter().map(Vec::len));
+		let mut nodes: HashMap<*const RefCell<Node<W>>, usize> = HashMap::new();
+		for (row_idx, row) in input.iter().enumerate() {
+			for (col_idx, node) in row.iter().enumerate() {
+				nodes.insert(Rc::as_ptr(node), graph.row_offsets[row_idx] + col_idx);
+			}
+		}
+
+		for (row_idx, row) in input.iter().enumerate() {
+			for (col_idx, node) in row.iter().enumerate() {
+				let node = node.borrow();
+				for (edge_idx, edge) in node.edges.iter().enumerate() {
+					let destination = match nodes.get(&Rc::as_ptr(&edge.destination)) {
+						Some(&destination) => destination,
+						None => {
+							return Err(MinPathError::DanglingDestination {
+								row: row_idx,
+								column: col_idx,
+								edge: edge_idx,
+							})
+						}
+					};
+					if !edge.weight.is_valid() {
+						return Err(MinPathError::InvalidWeight {
+							row: row_idx,
+							column: col_idx,
+							edge: edge_idx,
+						});
+					}
+					graph.destinations.push(destination);
+					graph.weights.push(edge.weight);
+				}
+				graph.edge_offsets.push(graph.destinations.len());
+
+				if node.weight.is_some_and(|weight| !weight.is_valid()) {
+					return Err(MinPathError::InvalidCost { row: row_idx, column: col_idx });
+				}
+				graph.node_weights.push(node.weight);
+			}
+		}
+
+		Ok(graph)
+	}
+}
+
+/// Orders the nodes of the graph whose edges out of node `n` lead to
+/// `destinations[edge_offsets[n]..edge_offsets[n + 1]]` so that every edge
+/// leads to a later node. Returns nodes that, in order, lead back to the first
+/// one if there is no such order.
+pub(crate) fn topological_order(
+	edge_offsets: &[usize],
+	destinations: &[usize],
+) -> Result<Vec<usize>, Vec<usize>> {
+	#[derive(Clone, Copy, PartialEq)]
+	enum Visit {
+		Pending,
+		InProgress,
+		Done,
+	}
+
+	let node_count = edge_offsets.len() - 1;
+	let mut visits = vec![Visit::Pending; node_count];
+	// Nodes in the order their depth first search finishes, the reverse of a
+	// topological order.
+	let mut finished = Vec::with_capacity(node_count);
+	for root in 0..node_count {
+		if visits[root] != Visit::Pending {
+			continue;
+		}
+
+		// Depth first search path from `root`, with the next edge to follow out of each node.
+		let mut stack = vec![(root, edge_offsets[root])];
+		visits[root] = Visit::InProgress;
+		while let Some((node, next_edge)) = stack.last_mut() {
+			if *next_edge == edge_offsets[*node + 1] {
+				visits[*node] = Visit::Done;
+				finished.push(*node);
+				stack.pop();
+				continue;
+			}
+			let dest = destinations[*next_edge];
+			*next_edge += 1;
+
+			match visits[dest] {
+				Visit::Pending => {
+					visits[dest] = Visit::InProgress;
+					stack.push((dest, edge_offsets[dest]));
+				}
+				Visit::InProgress => {
+					let start = stack.iter().position(|&(node, _)| node == dest).unwrap_or(0);
+					return Err(stack[start..].iter().map(|&(node, _)| node).collect());
+				}
+				Visit::Done => (),
+			}
+		}
+	}
+
+	finished.reverse();
+	Ok(finished)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn solves_skip_connections() {
+		let graph = Graph::from_rows(&[
+			vec![vec![((1, 0), 1), ((3, 0), 9)]],
+			vec![vec![((2, 0), 1)]],
+			vec![vec![((3, 0), 1)]],
+			vec![vec![]],
+		])
+		.unwrap();
+		assert_eq!(
+			graph.min_path(),
+			Ok(Some(Path {
+				cost: 3,
+				nodes: vec![(0, 0), (1, 0), (2, 0), (3, 0)],
+				edges: vec![0, 0, 0]
+			}))
+		);
+
+		// Skipping rows pays off once the detour costs more.
+		let graph = Graph::from_rows(&[
+			vec![vec![((1, 0), 5), ((2, 0), 4)]],
+			vec![vec![((2, 0), 1)]],
+			vec![vec![]],
+		])
+		.unwrap();
+		assert_eq!(
+			graph.min_path(),
+			Ok(Some(Path { cost: 4, nodes: vec![(0, 0), (2, 0)], edges: vec![1] }))
+		);
+	}
+
+	#[test]
+	fn reports_cycles() {
+		let graph = Graph::<usize>::from_rows(&[
+			vec![vec![((1, 0), 1)]],
+			vec![vec![((2, 0), 1)]],
+			vec![vec![((1, 0), 1)]],
+		])
+		.unwrap();
+		assert_eq!(graph.min_path(), Err(MinPathError::Cycle { nodes: vec![(1, 0), (2, 0)] }));
+	}
+}
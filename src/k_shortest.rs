@@ -0,0 +1,142 @@
+use std::cmp::Ordering;
+
+use crate::{layered::Objective, LayeredGraph, MinPathError, Path, Weight};
+
+/// One of the best paths to a node.
+#[derive(Clone, Copy)]
+struct RankedLabel<W> {
+	/// Weight of the path.
+	cost: W,
+	/// Previous node on the path, the rank of the path to it among its labels
+	/// and the edge taken from it, `None` for nodes in row 0.
+	predecessor: Option<(usize, usize, usize)>,
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Finds the `k` least cost distinct paths from row 0 to the last row,
+	/// cheapest first. Fewer paths are returned if the graph has less than `k`.
+	///
+	/// Any of the `k` best paths only goes through one of the `k` best paths to
+	/// each of its nodes, so the sweep keeps at most `k` paths per node instead
+	/// of enumerating all paths.
+	///
+	/// Like `min_path`, paths that overflow are dropped if no edge or node
+	/// weight is negative, and only reported if fewer than `k` paths do not.
+	pub fn k_shortest_paths(&self, k: usize) -> Result<Vec<Path<W>>, MinPathError> {
+		if k == 0 || self.row_count() < 2 {
+			return Ok(Vec::new());
+		}
+		let last_row = self.row_count() - 1;
+		let drops_overflows = self.drops_overflows(Objective::Minimize);
+
+		// `labels[n]` holds the best paths to node `n`, cheapest first, and
+		// `overflows[n]` the error of the first path to it that overflowed.
+		let mut labels: Vec<Vec<RankedLabel<W>>> = vec![Vec::new(); self.node_count()];
+		let mut overflows: Vec<Option<MinPathError>> = vec![None; self.node_count()];
+		for node in self.row(0) {
+			match self.visit(node, W::zero(), W::checked_add) {
+				Ok(cost) => labels[node].push(RankedLabel { cost, predecessor: None }),
+				Err(err) if drops_overflows => overflows[node] = Some(err),
+				Err(err) => return Err(err),
+			}
+		}
+
+		for row_idx in 0..last_row {
+			let next_row = self.row(row_idx + 1);
+			let (done, pending) = labels.split_at_mut(next_row.start);
+			for node in self.row(row_idx) {
+				// Paths extending one that overflowed overflow too.
+				if let Some(err) = overflows[node].clone() {
+					for edge in self.edges(node) {
+						overflows[self.destination(edge)].get_or_insert_with(|| err.clone());
+					}
+				}
+				for (rank, label) in done[node].iter().enumerate() {
+					for edge in self.edges(node) {
+						let dest = self.destination(edge);
+						let cost = match label.cost.checked_add(self.weight(edge)) {
+							Some(cost) => self.visit(dest, cost, W::checked_add),
+							None => Err(self.overflow(dest)),
+						};
+						match cost {
+							Ok(cost) => pending[dest - next_row.start]
+								.push(RankedLabel { cost, predecessor: Some((node, rank, edge)) }),
+							Err(err) if drops_overflows => {
+								overflows[dest].get_or_insert(err);
+							}
+							Err(err) => return Err(err),
+						}
+					}
+				}
+			}
+
+			for dest in next_row {
+				// The sort is stable, so ties keep the order edges were relaxed in.
+				labels[dest].sort_by(|a, b| a.cost.partial_cmp(&b.cost).unwrap_or(Ordering::Equal));
+				labels[dest].truncate(k);
+			}
+		}
+
+		let mut finals: Vec<(W, usize, usize)> = self
+			.row(last_row)
+			.flat_map(|node| {
+				labels[node].iter().enumerate().map(move |(rank, label)| (label.cost, node, rank))
+			})
+			.collect();
+		finals.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+		finals.truncate(k);
+		if finals.len() < k {
+			if let Some(err) = self.row(last_row).find_map(|node| overflows[node].clone()) {
+				return Err(err);
+			}
+		}
+
+		Ok(finals
+			.into_iter()
+			.map(|(cost, node, rank)| self.trace_ranked(&labels, cost, node, rank))
+			.collect())
+	}
+
+	/// Follows predecessors back from the `rank`th path to `node` to row 0.
+	fn trace_ranked(
+		&self,
+		labels: &[Vec<RankedLabel<W>>],
+		cost: W,
+		mut node: usize,
+		mut rank: usize,
+	) -> Path<W> {
+		let mut nodes = vec![self.position(node)];
+		let mut edges = Vec::new();
+		while let Some((pred, pred_rank, edge)) = labels[node][rank].predecessor {
+			nodes.push(self.position(pred));
+			edges.push(edge - self.edges(pred).start);
+			node = pred;
+			rank = pred_rank;
+		}
+		nodes.reverse();
+		edges.reverse();
+
+		Path { cost, nodes, edges }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::layered::example_graph;
+
+	#[test]
+	fn finds_k_shortest_paths() {
+		let graph = example_graph();
+
+		let paths = graph.k_shortest_paths(4).unwrap();
+		let costs: Vec<usize> = paths.iter().map(|path| path.cost).collect();
+		assert_eq!(costs, vec![5, 6, 6, 7]);
+		assert_eq!(paths[0], graph.min_path().unwrap().unwrap());
+		assert_eq!(paths[1].nodes, vec![(0, 1), (1, 0), (2, 0)]);
+		assert_eq!(paths[2].nodes, vec![(0, 1), (1, 1), (2, 1)]);
+
+		// There are only 6 paths in total.
+		assert_eq!(graph.k_shortest_paths(10).unwrap().len(), 6);
+		assert!(graph.k_shortest_paths(0).unwrap().is_empty());
+	}
+}
@@ -0,0 +1,673 @@
+use std::{cell::RefCell, collections::HashMap, convert::TryFrom, ops::Range, rc::Rc};
+
+use crate::{graph::topological_order, Input, MinPathError, Node, Path, Query, Weight};
+
+/// Compact layered graph for the min path cost problem.
+///
+/// Nodes are numbered row by row and edges are stored in contiguous arrays
+/// indexed by those numbers, so solving it involves no pointer chasing, borrow
+/// checks or reference counting, and it can be shared across threads.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayeredGraph<W = usize> {
+	/// Nodes of row `r` are `row_offsets[r]..row_offsets[r + 1]`.
+	row_offsets: Vec<usize>,
+	/// Edges out of node `n` are `edge_offsets[n]..edge_offsets[n + 1]`.
+	edge_offsets: Vec<usize>,
+	/// Node each edge leads to.
+	destinations: Vec<usize>,
+	/// Weight of each edge.
+	weights: Vec<W>,
+	/// Cost of visiting each node, if it has one.
+	node_weights: Vec<Option<W>>,
+	/// Number of secondary resources consumed by each edge.
+	resource_count: usize,
+	/// Resources consumed by edge `e` are
+	/// `resources[e * resource_count..(e + 1) * resource_count]`.
+	resources: Vec<W>,
+}
+
+/// Whether the sweep looks for the lightest or the heaviest path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Objective {
+	Minimize,
+	Maximize,
+}
+
+impl Objective {
+	/// Whether a path costing `candidate` is better than one costing `current`.
+	pub(crate) fn prefers<C: PartialOrd>(self, candidate: C, current: C) -> bool {
+		match self {
+			Objective::Minimize => candidate < current,
+			Objective::Maximize => candidate > current,
+		}
+	}
+}
+
+/// Dynamic programming state of a reachable node.
+#[derive(Clone, Copy)]
+pub(crate) struct Label<C> {
+	/// Weight of the best path to get to this node.
+	pub(crate) cost: C,
+	/// Previous node on the best path and the edge taken from it, `None` for
+	/// nodes in row 0.
+	pub(crate) predecessor: Option<(usize, usize)>,
+}
+
+/// Labels of the nodes reached by `LayeredGraph::relax`, and the error of the
+/// paths to each node that overflowed, which only matters if none did not.
+pub(crate) type Relaxed<C> = (Vec<Option<Label<C>>>, Vec<Option<MinPathError>>);
+
+/// Label of the best path to `node`, `None` if it can not be reached, or an
+/// error if all the paths to it overflow.
+pub(crate) fn reached<C: Copy>(
+	(labels, overflows): &Relaxed<C>,
+	node: usize,
+) -> Result<Option<Label<C>>, MinPathError> {
+	match (labels[node], &overflows[node]) {
+		(None, Some(err)) => Err(err.clone()),
+		(label, _) => Ok(label),
+	}
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Builds a graph from its adjacency lists: `rows[r][c]` holds the
+	/// `(destination column, weight)` of each edge out of the node in row `r`,
+	/// column `c`, leading to row `r + 1`.
+	pub fn from_rows(rows: &[Vec<Vec<(usize, W)>>]) -> Result<Self, MinPathError> {
+		if rows.is_empty() {
+			return Err(MinPathError::EmptyInput);
+		}
+
+		let mut graph = LayeredGraph::with_capacity(rows.len());
+		for (row_idx, row) in rows.iter().enumerate() {
+			let next_row_offset = graph.row_offsets[row_idx] + row.len();
+			let next_row_len = rows.get(row_idx + 1).map_or(0, Vec::len);
+			for (col_idx, edges) in row.iter().enumerate() {
+				for (edge_idx, &(dest_col_idx, weight)) in edges.iter().enumerate() {
+					if dest_col_idx >= next_row_len {
+						return Err(MinPathError::DanglingDestination {
+							row: row_idx,
+							column: col_idx,
+							edge: edge_idx,
+						});
+					}
+					if !weight.is_valid() {
+						return Err(MinPathError::InvalidWeight {
+							row: row_idx,
+							column: col_idx,
+							edge: edge_idx,
+						});
+					}
+					graph.push_edge(next_row_offset + dest_col_idx, weight);
+				}
+				graph.edge_offsets.push(graph.destinations.len());
+				graph.node_weights.push(None);
+			}
+			graph.row_offsets.push(next_row_offset);
+		}
+
+		Ok(graph)
+	}
+
+	fn with_capacity(row_count: usize) -> Self {
+		let mut row_offsets = Vec::with_capacity(row_count + 1);
+		row_offsets.push(0);
+		LayeredGraph {
+			row_offsets,
+			edge_offsets: vec![0],
+			destinations: Vec::new(),
+			weights: Vec::new(),
+			node_weights: Vec::new(),
+			resource_count: 0,
+			resources: Vec::new(),
+		}
+	}
+
+	fn push_edge(&mut self, destination: usize, weight: W) {
+		self.destinations.push(destination);
+		self.weights.push(weight);
+	}
+
+	/// Sets the cost of visiting the node at the given row and column, which is
+	/// then added to every path through it.
+	pub fn set_node_weight(
+		&mut self,
+		row_idx: usize,
+		col_idx: usize,
+		weight: W,
+	) -> Result<(), MinPathError> {
+		if row_idx >= self.row_count() || col_idx >= self.row(row_idx).len() {
+			return Err(MinPathError::InvalidColumn { row: row_idx, column: col_idx });
+		}
+		if !weight.is_valid() {
+			return Err(MinPathError::InvalidCost { row: row_idx, column: col_idx });
+		}
+		self.node_weights[self.row_offsets[row_idx] + col_idx] = Some(weight);
+		Ok(())
+	}
+
+	/// Number of rows.
+	pub fn row_count(&self) -> usize {
+		self.row_offsets.len() - 1
+	}
+
+	/// Total number of nodes.
+	pub fn node_count(&self) -> usize {
+		self.edge_offsets.len() - 1
+	}
+
+	/// Total number of edges.
+	pub fn edge_count(&self) -> usize {
+		self.destinations.len()
+	}
+
+	/// Number of secondary resources consumed by each edge.
+	pub fn resource_count(&self) -> usize {
+		self.resource_count
+	}
+
+	/// Nodes of the given row.
+	pub(crate) fn row(&self, row_idx: usize) -> Range<usize> {
+		self.row_offsets[row_idx]..self.row_offsets[row_idx + 1]
+	}
+
+	/// Edges out of the given node.
+	pub(crate) fn edges(&self, node: usize) -> Range<usize> {
+		self.edge_offsets[node]..self.edge_offsets[node + 1]
+	}
+
+	/// Node the given edge leads to.
+	pub(crate) fn destination(&self, edge: usize) -> usize {
+		self.destinations[edge]
+	}
+
+	/// Weight of the given edge.
+	pub(crate) fn weight(&self, edge: usize) -> W {
+		self.weights[edge]
+	}
+
+	/// Resources consumed by the given edge.
+	pub(crate) fn resources(&self, edge: usize) -> &[W] {
+		&self.resources[edge * self.resource_count..(edge + 1) * self.resource_count]
+	}
+
+	/// Cost of visiting the given node, if it has one.
+	pub(crate) fn node_weight(&self, node: usize) -> Option<W> {
+		self.node_weights[node]
+	}
+
+	/// Whether a path whose cost overflows can be dropped in favor of any other
+	/// one: when minimizing, and no edge or node weighs less than nothing, the
+	/// paths it leads to overflow too.
+	pub(crate) fn drops_overflows(&self, objective: Objective) -> bool {
+		objective == Objective::Minimize
+			&& self
+				.weights
+				.iter()
+				.chain(self.node_weights.iter().flatten())
+				.all(|&weight| weight >= W::zero())
+	}
+
+	/// Adds the cost of visiting `node`, if any, to the cost of a path reaching it.
+	pub(crate) fn visit<C>(
+		&self,
+		node: usize,
+		cost: C,
+		add: impl Fn(C, W) -> Option<C>,
+	) -> Result<C, MinPathError> {
+		match self.node_weights[node] {
+			Some(weight) => add(cost, weight).ok_or_else(|| self.overflow(node)),
+			None => Ok(cost),
+		}
+	}
+
+	/// `(row, column)` of the given node.
+	pub(crate) fn position(&self, node: usize) -> (usize, usize) {
+		let row_idx = self.row_offsets.partition_point(|&offset| offset <= node) - 1;
+		(row_idx, node - self.row_offsets[row_idx])
+	}
+
+	/// Finds the least cost path from row 0 to the last row, `None` if there is
+	/// no such path. Like `min_path_cost`, a graph with fewer than two rows has
+	/// no path.
+	///
+	/// Reports `MinPathError::Overflow` if the cost of the least cost path does
+	/// not fit in a `W`. Paths that overflow only lose to cheaper ones if no
+	/// edge or node weight is negative, otherwise the first overflow found is
+	/// reported.
+	pub fn min_path(&self) -> Result<Option<Path<W>>, MinPathError> {
+		self.query(&Query::new())
+	}
+
+	/// Finds the maximum cost path from row 0 to the last row, e.g. the critical
+	/// path of a staged pipeline, `None` if there is no such path.
+	///
+	/// Reports `MinPathError::Overflow` at the first node whose path cost does
+	/// not fit in a `W`.
+	pub fn max_path(&self) -> Result<Option<Path<W>>, MinPathError> {
+		self.query(&Query::new().maximize())
+	}
+
+	/// Same as `min_path`, but path costs saturate at the bounds of `W` instead
+	/// of overflowing. Saturated paths all cost the same, so the returned path
+	/// is only guaranteed to be the least cost one if its cost is not saturated.
+	pub fn min_path_saturating(&self) -> Option<Path<W>> {
+		self.sweep(
+			Objective::Minimize,
+			self.row(0).map(|node| (node, W::zero())),
+			self.last_row().map(|node| (node, W::zero())),
+			|cost, weight| Some(cost.saturating_add(weight)),
+		)
+		.unwrap_or_else(|_| unreachable!("saturating addition never overflows"))
+	}
+
+	/// Finds the best path from the query's sources to its targets, `None` if
+	/// there is no such path. The path cost includes the initial cost of its
+	/// source and the terminal cost of its target.
+	///
+	/// Without tie breaks, the first of the best paths found in row order is
+	/// returned, see `Query::tie_break` to choose between them.
+	pub fn query(&self, query: &Query<W>) -> Result<Option<Path<W>>, MinPathError> {
+		if !query.tie_breaks.is_empty() {
+			return self.tie_broken_query(query);
+		}
+		let (sources, targets) = query.nodes(self)?;
+		self.sweep(query.objective, sources, targets, W::checked_add)
+	}
+
+	/// Nodes of the last row, empty if the graph has fewer than two rows.
+	pub(crate) fn last_row(&self) -> Range<usize> {
+		match self.row_count() {
+			0 | 1 => 0..0,
+			row_count => self.row(row_count - 1),
+		}
+	}
+
+	/// Finds the path that best fits the `objective` from the `(node, initial
+	/// cost)` sources to the `(node, terminal cost)` targets, accumulating path
+	/// costs with `add`, which returns `None` on overflow.
+	fn sweep<C: Copy + PartialOrd>(
+		&self,
+		objective: Objective,
+		sources: impl IntoIterator<Item = (usize, C)>,
+		targets: impl IntoIterator<Item = (usize, W)>,
+		add: impl Fn(C, W) -> Option<C>,
+	) -> Result<Option<Path<C>>, MinPathError> {
+		if self.row_count() < 2 {
+			return Ok(None);
+		}
+		let drops_overflows = self.drops_overflows(objective);
+		let relaxed = self.relax(objective, sources, &add)?;
+
+		// We are on the last row we look for the best path to get here.
+		let mut final_path: Option<(C, usize)> = None;
+		let mut maybe_overflow = None;
+		for (node, terminal_cost) in targets {
+			let cost = match reached(&relaxed, node) {
+				Ok(Some(label)) => {
+					add(label.cost, terminal_cost).ok_or_else(|| self.overflow(node))
+				}
+				Ok(None) => continue,
+				Err(err) => Err(err),
+			};
+			match cost {
+				Ok(cost) => match final_path {
+					Some((final_cost, _)) if !objective.prefers(cost, final_cost) => (),
+					_ => final_path = Some((cost, node)),
+				},
+				// A negative terminal cost could bring an overflowing path back in range.
+				Err(err) if drops_overflows && terminal_cost >= W::zero() => {
+					maybe_overflow.get_or_insert(err);
+				}
+				Err(err) => return Err(err),
+			}
+		}
+
+		match (final_path, maybe_overflow) {
+			(None, Some(err)) => Err(err),
+			(final_path, _) => {
+				Ok(final_path.map(|(cost, node)| self.trace(&relaxed.0, cost, node)))
+			}
+		}
+	}
+
+	/// Relaxes edges row by row from the `(node, initial cost)` sources, keeping
+	/// the path to each node that best fits the `objective`. `labels[n]` is
+	/// `None` if node `n` can not be reached.
+	///
+	/// Paths that overflow are dropped if `drops_overflows`, see `reached` for
+	/// the nodes only they reach, and reported otherwise.
+	pub(crate) fn relax<C: Copy + PartialOrd>(
+		&self,
+		objective: Objective,
+		sources: impl IntoIterator<Item = (usize, C)>,
+		add: impl Fn(C, W) -> Option<C>,
+	) -> Result<Relaxed<C>, MinPathError> {
+		let drops_overflows = self.drops_overflows(objective);
+		let mut labels: Vec<Option<Label<C>>> = vec![None; self.node_count()];
+		let mut overflows: Vec<Option<MinPathError>> = vec![None; self.node_count()];
+		for (node, cost) in sources {
+			let cost = match self.visit(node, cost, &add) {
+				Ok(cost) => cost,
+				Err(err) if drops_overflows => {
+					overflows[node].get_or_insert(err);
+					continue;
+				}
+				Err(err) => return Err(err),
+			};
+			match labels[node] {
+				Some(label) if !objective.prefers(cost, label.cost) => (),
+				_ => labels[node] = Some(Label { cost, predecessor: None }),
+			}
+		}
+
+		for node in 0..self.last_row().start {
+			let src_cost = match labels[node] {
+				Some(label) => label.cost,
+				// We are at an inaccessible node, the paths through it overflow if any reach it.
+				None => {
+					if let Some(err) = overflows[node].clone() {
+						for edge in self.edges(node) {
+							overflows[self.destinations[edge]].get_or_insert_with(|| err.clone());
+						}
+					}
+					continue;
+				}
+			};
+
+			for edge in self.edges(node) {
+				let dest = self.destinations[edge];
+				let weight_to_dest = match add(src_cost, self.weights[edge]) {
+					Some(weight_to_dest) => self.visit(dest, weight_to_dest, &add),
+					None => Err(self.overflow(dest)),
+				};
+				let weight_to_dest = match weight_to_dest {
+					Ok(weight_to_dest) => weight_to_dest,
+					Err(err) if drops_overflows => {
+						overflows[dest].get_or_insert(err);
+						continue;
+					}
+					Err(err) => return Err(err),
+				};
+
+				// Potentially update the destination node's best path.
+				match labels[dest] {
+					Some(label) if !objective.prefers(weight_to_dest, label.cost) => (),
+					_ => {
+						labels[dest] =
+							Some(Label { cost: weight_to_dest, predecessor: Some((node, edge)) })
+					}
+				};
+			}
+		}
+
+		Ok((labels, overflows))
+	}
+
+	/// Error for a path to the given node whose cost overflows.
+	pub(crate) fn overflow(&self, node: usize) -> MinPathError {
+		let (row, column) = self.position(node);
+		MinPathError::Overflow { row, column }
+	}
+
+	/// Follows predecessors back from `node` to row 0.
+	pub(crate) fn trace<C: Copy>(
+		&self,
+		labels: &[Option<Label<C>>],
+		cost: C,
+		mut node: usize,
+	) -> Path<C> {
+		let mut nodes = vec![self.position(node)];
+		let mut edges = Vec::new();
+		while let Some((pred, edge)) = labels[node].and_then(|label| label.predecessor) {
+			nodes.push(self.position(pred));
+			edges.push(edge - self.edge_offsets[pred]);
+			node = pred;
+		}
+		nodes.reverse();
+		edges.reverse();
+
+		Path { cost, nodes, edges }
+	}
+}
+
+impl LayeredGraph<usize> {
+	/// Same as `min_path`, but path costs are accumulated as `u128`, which can
+	/// not overflow for any graph that fits in memory.
+	pub fn min_path_wide(&self) -> Option<Path<u128>> {
+		self.sweep(
+			Objective::Minimize,
+			self.row(0).map(|node| (node, 0)),
+			self.last_row().map(|node| (node, 0)),
+			|cost: u128, weight| Some(cost + weight as u128),
+		)
+		.unwrap_or_else(|_| unreachable!("wide addition never overflows"))
+	}
+}
+
+impl<W: Weight> TryFrom<&Input<W>> for LayeredGraph<W> {
+	type Error = MinPathError;
+
+	/// Converts the input, checking that it is non-empty and that every edge
+	/// leads to a node in the row right after its source. Rows may have any
+	/// number of nodes, see `validate` for the stricter NxN check.
+	///
+	/// Edges may have resource vectors of different lengths, the missing
+	/// resources of the shorter ones are not consumed.
+	fn try_from(input: &Input<W>) -> Result<Self, Self::Error> {
+		if input.is_empty() {
+			return Err(MinPathError::EmptyInput);
+		}
+
+		let mut graph = LayeredGraph::with_capacity(input.len());
+		let mut positions: HashMap<*const RefCell<Node<W>>, (usize, usize)> = HashMap::new();
+		for (row_idx, row) in input.iter().enumerate() {
+			for (col_idx, node) in row.iter().enumerate() {
+				positions.insert(Rc::as_ptr(node), (row_idx, col_idx));
+				let resource_count =
+					node.borrow().edges.iter().map(|edge| edge.resources.len()).max();
+				graph.resource_count = graph.resource_count.max(resource_count.unwrap_or(0));
+			}
+			graph.row_offsets.push(graph.row_offsets[row_idx] + row.len());
+		}
+
+		// First edge that does not lead to the next row, and whether any edge leads
+		// back to its own or an earlier row, which may close a cycle.
+		let mut maybe_non_adjacent = None;
+		let mut leads_back = false;
+		for (row_idx, row) in input.iter().enumerate() {
+			for (col_idx, node) in row.iter().enumerate() {
+				for (edge_idx, edge) in node.borrow().edges.iter().enumerate() {
+					let destination = match positions.get(&Rc::as_ptr(&edge.destination)) {
+						Some(&destination) => destination,
+						None => {
+							return Err(MinPathError::DanglingDestination {
+								row: row_idx,
+								column: col_idx,
+								edge: edge_idx,
+							})
+						}
+					};
+					if destination.0 != row_idx + 1 && maybe_non_adjacent.is_none() {
+						maybe_non_adjacent = Some(MinPathError::NonAdjacentEdge {
+							row: row_idx,
+							column: col_idx,
+							edge: edge_idx,
+							destination,
+						});
+					}
+					if !edge.weight.is_valid()
+						|| edge.resources.iter().any(|resource| !resource.is_valid())
+					{
+						return Err(MinPathError::InvalidWeight {
+							row: row_idx,
+							column: col_idx,
+							edge: edge_idx,
+						});
+					}
+					leads_back |= destination.0 <= row_idx;
+					graph.push_edge(graph.row_offsets[destination.0] + destination.1, edge.weight);
+					graph.resources.extend_from_slice(&edge.resources);
+					graph
+						.resources
+						.resize(graph.destinations.len() * graph.resource_count, W::zero());
+				}
+				graph.edge_offsets.push(graph.destinations.len());
+
+				let node_weight = node.borrow().weight;
+				if node_weight.is_some_and(|weight| !weight.is_valid()) {
+					return Err(MinPathError::InvalidCost { row: row_idx, column: col_idx });
+				}
+				graph.node_weights.push(node_weight);
+			}
+		}
+
+		if leads_back {
+			if let Err(cycle) = topological_order(&graph.edge_offsets, &graph.destinations) {
+				let nodes = cycle.into_iter().map(|node| graph.position(node)).collect();
+				return Err(MinPathError::Cycle { nodes });
+			}
+		}
+		match maybe_non_adjacent {
+			Some(err) => Err(err),
+			None => Ok(graph),
+		}
+	}
+}
+
+/// Graph of the `min_path_cost` example, whose least cost path costs 5.
+#[cfg(test)]
+pub(crate) fn example_graph() -> LayeredGraph {
+	LayeredGraph::from_rows(&[
+		vec![vec![(0, 2), (1, 3)], vec![(0, 0), (1, 1)]],
+		vec![vec![(0, 6)], vec![(0, 4), (1, 5)]],
+		vec![vec![], vec![]],
+	])
+	.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Fixed, TieBreak};
+
+	#[test]
+	fn solves_from_rows() {
+		let graph = example_graph();
+		assert_eq!((graph.row_count(), graph.node_count(), graph.edge_count()), (3, 6, 7));
+		assert_eq!(
+			graph.min_path(),
+			Ok(Some(Path { cost: 5, nodes: vec![(0, 1), (1, 1), (2, 0)], edges: vec![1, 0] }))
+		);
+		assert_eq!(LayeredGraph::<usize>::from_rows(&[vec![vec![]]]).unwrap().min_path(), Ok(None));
+		assert_eq!(
+			LayeredGraph::from_rows(&[vec![vec![(1, 1)]], vec![vec![]]]),
+			Err(MinPathError::DanglingDestination { row: 0, column: 0, edge: 0 })
+		);
+	}
+
+	#[test]
+	fn finds_max_path() {
+		let graph = example_graph();
+		assert_eq!(
+			graph.max_path(),
+			Ok(Some(Path { cost: 8, nodes: vec![(0, 0), (1, 0), (2, 0)], edges: vec![0, 0] }))
+		);
+
+		// Nodes that can not be reached are skipped, no matter how heavy their edges.
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, 1)]],
+			vec![vec![(0, 1)], vec![(0, 9)]],
+			vec![vec![]],
+		])
+		.unwrap();
+		assert_eq!(graph.max_path().unwrap().map(|path| path.cost), Some(2));
+	}
+
+	#[test]
+	fn detects_overflow() {
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, usize::MAX)]],
+			vec![vec![(0, 1)]],
+			vec![vec![]],
+		])
+		.unwrap();
+		assert_eq!(graph.min_path(), Err(MinPathError::Overflow { row: 2, column: 0 }));
+		assert_eq!(graph.min_path_saturating().map(|path| path.cost), Some(usize::MAX));
+		assert_eq!(graph.min_path_wide().map(|path| path.cost), Some(usize::MAX as u128 + 1));
+	}
+
+	#[test]
+	fn drops_overflowing_paths() {
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, 1)]],
+			vec![vec![(0, usize::MAX), (1, 0)]],
+			vec![vec![], vec![]],
+		])
+		.unwrap();
+		let expected = Path { cost: 1, nodes: vec![(0, 0), (1, 0), (2, 1)], edges: vec![0, 1] };
+		assert_eq!(graph.min_path(), Ok(Some(expected.clone())));
+		assert_eq!(
+			graph.query(&Query::new().tie_break(TieBreak::ColumnChanges)),
+			Ok(Some(expected.clone()))
+		);
+		assert_eq!(graph.k_shortest_paths(1), Ok(vec![expected.clone()]));
+		assert_eq!(
+			graph.optimal_paths().unwrap().map(|paths| paths.iter().collect()),
+			Some(vec![expected])
+		);
+
+		// The second least cost path, and the only one to column 0, overflow.
+		let overflow = MinPathError::Overflow { row: 2, column: 0 };
+		assert_eq!(graph.k_shortest_paths(2), Err(overflow.clone()));
+		assert_eq!(graph.query(&Query::new().target(0)), Err(overflow));
+		assert!(graph.all_pairs().is_err());
+	}
+
+	#[test]
+	fn solves_generic_weights() {
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, 0.5), (1, 0.25)]],
+			vec![vec![(0, 0.5)], vec![(0, 1.0)]],
+			vec![vec![]],
+		])
+		.unwrap();
+		assert_eq!(graph.min_path().unwrap().map(|path| path.cost), Some(1.0));
+		assert_eq!(
+			LayeredGraph::from_rows(&[vec![vec![(0, f64::NAN)]], vec![vec![]]]),
+			Err(MinPathError::InvalidWeight { row: 0, column: 0, edge: 0 })
+		);
+
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, Fixed::<2>(10)), (0, Fixed(20))]],
+			vec![vec![]],
+		])
+		.unwrap();
+		assert_eq!(
+			graph.min_path().unwrap().map(|path| path.cost.to_string()),
+			Some("0.10".to_string())
+		);
+	}
+
+	#[test]
+	fn adds_node_weights() {
+		let mut graph = example_graph();
+		graph.set_node_weight(0, 1, 3).unwrap();
+		graph.set_node_weight(2, 0, 2).unwrap();
+
+		let path = graph.min_path().unwrap().unwrap();
+		assert_eq!((path.cost, path.nodes), (8, vec![(0, 0), (1, 1), (2, 1)]));
+		assert_eq!(graph.k_shortest_paths(1).unwrap()[0].cost, 8);
+		assert_eq!(graph.optimal_paths().unwrap().unwrap().cost(), 8);
+		assert_eq!(
+			graph.set_node_weight(3, 0, 1),
+			Err(MinPathError::InvalidColumn { row: 3, column: 0 })
+		);
+	}
+
+	#[test]
+	fn is_send_and_sync() {
+		fn assert_send_sync<T: Send + Sync>() {}
+		assert_send_sync::<LayeredGraph<f64>>();
+	}
+}
@@ -1,92 +1,175 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, convert::TryFrom, rc::Rc};
 
-type NodePointer = Rc<RefCell<Node>>;
-type Input = Vec<Vec<NodePointer>>;
+mod a_star;
+mod all_pairs;
+mod bellman_ford;
+mod bidirectional;
+mod bottleneck;
+mod constrained;
+mod dijkstra;
+mod error;
+mod graph;
+mod k_shortest;
+mod layered;
+mod matrix;
+mod optimal;
+mod pareto;
+mod query;
+mod reliability;
+mod semiring;
+mod tie_break;
+mod weight;
+
+pub use a_star::{Heuristic, RowMinimum};
+pub use all_pairs::CostTable;
+pub use error::MinPathError;
+pub use graph::Graph;
+pub use layered::LayeredGraph;
+pub use matrix::TransitionMatrix;
+pub use optimal::{OptimalPathIter, OptimalPaths, PathCount};
+pub use pareto::ParetoPath;
+pub use query::Query;
+pub use semiring::{
+	Boolean, Extended, MaxMin, MaxPlus, MinMax, MinPlus, Selective, Semiring, SumProduct,
+};
+pub use tie_break::TieBreak;
+pub use weight::{Fixed, Weight};
+
+type NodePointer<W = usize> = Rc<RefCell<Node<W>>>;
+type Input<W = usize> = Vec<Vec<NodePointer<W>>>;
 
 /// Unidirectional, weighted edge to a `Node`.
-pub struct Edge {
-	/// Weight of the edge.
-	weight: usize, // Assume no negative values, so we are using unsigned integers.
+pub struct Edge<W = usize> {
+	/// Weight of the edge. Negative weights are fine as edges only lead to the
+	/// next row, so there are no cycles to keep lowering a path's cost.
+	weight: W,
+	/// Amounts of secondary resources, e.g. time or fuel, consumed by taking
+	/// the edge, see `LayeredGraph::constrained_path`.
+	resources: Vec<W>,
 	/// Node the edge leads to.
-	destination: NodePointer,
+	destination: NodePointer<W>,
 }
 
-impl Edge {
-	pub fn new(weight: usize, destination: NodePointer) -> Self {
-		Edge { weight, destination }
+impl<W> Edge<W> {
+	pub fn new(weight: W, destination: NodePointer<W>) -> Self {
+		Edge { weight, resources: Vec::new(), destination }
+	}
+
+	pub fn with_resources(weight: W, resources: Vec<W>, destination: NodePointer<W>) -> Self {
+		Edge { weight, resources, destination }
 	}
 }
 
 /// Node for min path cost problem.
-#[derive(Default)]
-pub struct Node {
+pub struct Node<W = usize> {
 	/// Edges to destination node.
-	edges: Vec<Edge>,
-	/// Weight of the "lightest" path to get to this node.
-	maybe_min_path: Option<usize>,
+	edges: Vec<Edge<W>>,
+	/// Cost of visiting the node itself, added to every path through it.
+	weight: Option<W>,
 }
 
-impl Node {
-	pub fn new(edges: Vec<Edge>) -> Self {
-		Node { edges, maybe_min_path: None }
+impl<W> Node<W> {
+	pub fn new(edges: Vec<Edge<W>>) -> Self {
+		Node { edges, weight: None }
+	}
+
+	pub fn with_weight(weight: W, edges: Vec<Edge<W>>) -> Self {
+		Node { edges, weight: Some(weight) }
+	}
+
+	/// Node without edges nor weight, `Node::default` for any weight type.
+	pub fn empty() -> Self {
+		Node::new(Vec::new())
 	}
 }
 
+// Only for the default weight type, so `Node::default()` infers it.
+impl Default for Node {
+	fn default() -> Self {
+		Node::empty()
+	}
+}
 
+/// Path from row 0 to the last row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<W = usize> {
+	/// Total weight of the edges and nodes along the path.
+	pub cost: W,
+	/// `(row, column)` of each node on the path, starting in row 0.
+	pub nodes: Vec<(usize, usize)>,
+	/// Index into `Node::edges` of the edge taken out of each node but the last.
+	pub edges: Vec<usize>,
+}
 
 // Given an NxN matrix of connected “nodes” with weighted edges, where a node in
 // row i can only connect to nodes in row i+1, find the least cost path from row
-// 0 to row N-16
+// 0 to row N-1
 // Inputs: 2d matrix of elements of type `Node`
 // Output: ~~integer~~ `Option<usize>` where `None` denotes no possible path
 //
-// Assumes inputs are validated
-pub fn min_path_cost(input: Input) -> Option<usize> {
-	let mut final_min_path = None;
-	let last_row = input.len() - 1;
+// Assumes inputs are validated, see `validate` and `try_min_path_cost`
+pub fn min_path_cost<W: Weight>(input: Input<W>) -> Option<W> {
+	min_path(&input).map(|path| path.cost)
+}
+
+/// Same as `min_path_cost`, but also returns the nodes and edges the least cost
+/// path goes through. `None` denotes no possible path.
+///
+/// The input is left untouched, so it can be solved any number of times. Of
+/// several least cost paths, the one returned depends on the order of rows and
+/// edges in the input, use `LayeredGraph::query` with `Query::tie_break` for a
+/// path that only depends on the graph.
+///
+/// # Panics
+///
+/// Panics if the input is empty, its edges do not only lead from one row to the
+/// next or the path cost overflows, use `try_min_path` to get an error instead.
+pub fn min_path<W: Weight>(input: &Input<W>) -> Option<Path<W>> {
+	match LayeredGraph::try_from(input).and_then(|graph| graph.min_path()) {
+		Ok(maybe_path) => maybe_path,
+		Err(err) => panic!("{}", err),
+	}
+}
+
+/// Checks that the input is an NxN matrix whose edges only lead from a row to
+/// the next one, reporting the first violation found.
+pub fn validate<W: Weight>(input: &Input<W>) -> Result<(), MinPathError> {
+	validated_graph(input).map(drop)
+}
+
+/// Same as `min_path_cost`, but validates the input first.
+pub fn try_min_path_cost<W: Weight>(input: &Input<W>) -> Result<Option<W>, MinPathError> {
+	Ok(try_min_path(input)?.map(|path| path.cost))
+}
 
+/// Same as `min_path`, but validates the input first.
+pub fn try_min_path<W: Weight>(input: &Input<W>) -> Result<Option<Path<W>>, MinPathError> {
+	validated_graph(input)?.min_path()
+}
+
+/// Same as `try_min_path`, but edges may lead from any row to any other, e.g.
+/// skipping rows, as long as they do not form a cycle, and rows may have any
+/// number of nodes. Inputs whose edges all lead to the next row are still
+/// solved as a `LayeredGraph`.
+pub fn try_dag_min_path<W: Weight>(input: &Input<W>) -> Result<Option<Path<W>>, MinPathError> {
+	match LayeredGraph::try_from(input) {
+		Err(MinPathError::NonAdjacentEdge { .. }) => Graph::try_from(input)?.min_path(),
+		maybe_graph => maybe_graph?.min_path(),
+	}
+}
+
+fn validated_graph<W: Weight>(input: &Input<W>) -> Result<LayeredGraph<W>, MinPathError> {
 	for (row_idx, row) in input.iter().enumerate() {
-		for node in row.iter() {
-			let node = node.borrow_mut();
-			if row_idx != 0 && node.maybe_min_path.is_none() {
-				// We are at an inaccessible node.
-				continue;
-			} else if row_idx == last_row {
-				// We are on the last row we look for the min path to get here.
-				if let Some(min_path) = node.maybe_min_path {
-					if final_min_path.unwrap_or(usize::MAX) > min_path {
-						final_min_path = Some(min_path)
-					}
-				}
-			} else {
-				// We are at a non-terminal node.
-				for edge in node.edges.iter() {
-					let weight_to_dest = if row_idx == 0 {
-						// This is a starting node, so the path only consists of 1 edge.
-						edge.weight
-					} else if let Some(src_min_path) = node.maybe_min_path {
-						edge.weight + src_min_path
-					} else {
-						// We already skipped non-accessible nodes, thus `node.maybe_min_path`
-						// is always `Some` so we should never reach here.
-						continue;
-					};
-
-					// Potentially update the destination node's min path.
-					let dest_maybe_min_path = edge.destination.borrow().maybe_min_path;
-					match dest_maybe_min_path {
-						None => edge.destination.borrow_mut().maybe_min_path = Some(weight_to_dest),
-						Some(dest_min_path) if dest_min_path > weight_to_dest => {
-							edge.destination.borrow_mut().maybe_min_path = Some(weight_to_dest)
-						}
-						_ => (),
-					};
-				}
-			}
+		if row.len() != input.len() {
+			return Err(MinPathError::NotSquare {
+				row: row_idx,
+				len: row.len(),
+				expected: input.len(),
+			});
 		}
 	}
 
-	return final_min_path;
+	LayeredGraph::try_from(input)
 }
 
 #[cfg(test)]
@@ -122,4 +205,153 @@ mod tests {
 		];
 		assert_eq!(min_path_cost(simple_input), Some(5));
 	}
+
+	#[test]
+	fn empty_nodes_of_any_weight() {
+		let node: Node<f64> = Node::empty();
+		assert!(node.edges.is_empty() && node.weight.is_none());
+	}
+
+	#[test]
+	fn min_path_reports_route() {
+		let r2c0 = node_pointer(vec![]);
+		let r2c1 = node_pointer(vec![]);
+
+		let r1c0 = node_pointer(vec![Edge::new(6, r2c0.clone())]);
+		let r1c1 = node_pointer(vec![Edge::new(4, r2c0.clone()), Edge::new(5, r2c1.clone())]);
+
+		let r0c0 = node_pointer(vec![Edge::new(2, r1c0.clone()), Edge::new(3, r1c1.clone())]);
+		let r0c1 = node_pointer(vec![Edge::new(0, r1c0.clone()), Edge::new(1, r1c1.clone())]);
+
+		let simple_input = vec![
+			vec![r0c0, r0c1],
+			vec![r1c0, r1c1],
+			vec![r2c0, r2c1]
+		];
+		let expected =
+			Some(Path { cost: 5, nodes: vec![(0, 1), (1, 1), (2, 0)], edges: vec![1, 0] });
+		assert_eq!(min_path(&simple_input), expected);
+		// Solving does not mutate the input, so repeated queries agree.
+		assert_eq!(min_path(&simple_input), expected);
+
+		let unreachable_input = vec![
+			vec![node_pointer(vec![])],
+			vec![node_pointer(vec![])]
+		];
+		assert_eq!(min_path(&unreachable_input), None);
+	}
+
+	#[test]
+	fn validate_reports_locations() {
+		assert_eq!(validate::<usize>(&vec![]), Err(MinPathError::EmptyInput));
+		assert_eq!(
+			try_min_path_cost(&vec![vec![node_pointer(vec![]), node_pointer(vec![])]]),
+			Err(MinPathError::NotSquare { row: 0, len: 2, expected: 1 })
+		);
+
+		let dangling_input = vec![vec![node_pointer(vec![Edge::new(1, node_pointer(vec![]))])]];
+		assert_eq!(
+			validate(&dangling_input),
+			Err(MinPathError::DanglingDestination { row: 0, column: 0, edge: 0 })
+		);
+
+		let r2c0 = node_pointer(vec![]);
+		let r1c0 = node_pointer(vec![Edge::new(1, r2c0.clone())]);
+		let r0c0 = node_pointer(vec![Edge::new(1, r1c0.clone()), Edge::new(1, r2c0.clone())]);
+		let skipping_input = vec![vec![r0c0], vec![r1c0], vec![r2c0]];
+		assert_eq!(
+			LayeredGraph::try_from(&skipping_input),
+			Err(MinPathError::NonAdjacentEdge { row: 0, column: 0, edge: 1, destination: (2, 0) })
+		);
+
+		let r1c0 = node_pointer(vec![]);
+		let r0c0 = node_pointer(vec![Edge::new(1, r1c0.clone())]);
+		r1c0.borrow_mut().edges.push(Edge::new(1, r0c0.clone()));
+		let cyclic_input = vec![vec![r0c0.clone()], vec![r1c0.clone()]];
+		assert_eq!(
+			LayeredGraph::try_from(&cyclic_input),
+			Err(MinPathError::Cycle { nodes: vec![(0, 0), (1, 0)] })
+		);
+		// Break the reference cycle so the nodes are freed.
+		r1c0.borrow_mut().edges.clear();
+	}
+
+	#[test]
+	fn dag_min_path_skips_rows() {
+		let r2c0 = node_pointer(vec![]);
+		let r1c0 = node_pointer(vec![Edge::new(1, r2c0.clone())]);
+		let r0c0 = node_pointer(vec![Edge::new(1, r1c0.clone()), Edge::new(1, r2c0.clone())]);
+		let skipping_input = vec![vec![r0c0], vec![r1c0], vec![r2c0]];
+		assert_eq!(
+			try_dag_min_path(&skipping_input),
+			Ok(Some(Path { cost: 1, nodes: vec![(0, 0), (2, 0)], edges: vec![1] }))
+		);
+
+		let r1c0 = node_pointer(vec![]);
+		let r0c0 = node_pointer(vec![Edge::new(1, r1c0.clone())]);
+		r1c0.borrow_mut().edges.push(Edge::new(1, r0c0.clone()));
+		let cyclic_input = vec![vec![r0c0.clone()], vec![r1c0.clone()]];
+		assert_eq!(
+			try_dag_min_path(&cyclic_input),
+			Err(MinPathError::Cycle { nodes: vec![(0, 0), (1, 0)] })
+		);
+		// Break the reference cycle so the nodes are freed.
+		r1c0.borrow_mut().edges.clear();
+	}
+
+	#[test]
+	fn negative_weights() {
+		fn signed_node_pointer(edges: Vec<Edge<i64>>) -> NodePointer<i64> {
+			Rc::new(RefCell::new(Node::new(edges)))
+		}
+
+		let r2c0 = signed_node_pointer(vec![]);
+		let r2c1 = signed_node_pointer(vec![]);
+
+		let r1c0 = signed_node_pointer(vec![Edge::new(-6, r2c0.clone())]);
+		let r1c1 =
+			signed_node_pointer(vec![Edge::new(-4, r2c0.clone()), Edge::new(-5, r2c1.clone())]);
+
+		let r0c0 =
+			signed_node_pointer(vec![Edge::new(-2, r1c0.clone()), Edge::new(-3, r1c1.clone())]);
+		let r0c1 =
+			signed_node_pointer(vec![Edge::new(0, r1c0.clone()), Edge::new(-1, r1c1.clone())]);
+
+		let negative_input = vec![
+			vec![r0c0, r0c1],
+			vec![r1c0, r1c1],
+			vec![r2c0, r2c1]
+		];
+		assert_eq!(
+			min_path(&negative_input),
+			Some(Path { cost: -8, nodes: vec![(0, 0), (1, 0), (2, 0)], edges: vec![0, 0] })
+		);
+
+		let underflowing_graph =
+			LayeredGraph::from_rows(&[vec![vec![(0, i8::MIN)]], vec![vec![(0, -1)]], vec![vec![]]])
+				.unwrap();
+		assert_eq!(
+			underflowing_graph.min_path(),
+			Err(MinPathError::Overflow { row: 2, column: 0 })
+		);
+		assert_eq!(underflowing_graph.min_path_saturating().map(|path| path.cost), Some(i8::MIN));
+	}
+
+	#[test]
+	fn node_weights() {
+		let r1c0 = Rc::new(RefCell::new(Node::with_weight(10, vec![])));
+		let r1c1 = Rc::new(RefCell::new(Node::with_weight(1, vec![])));
+
+		let r0c0 = Rc::new(RefCell::new(Node::with_weight(5, vec![Edge::new(1, r1c0.clone())])));
+		let r0c1 = Rc::new(RefCell::new(Node::with_weight(
+			1,
+			vec![Edge::new(3, r1c0.clone()), Edge::new(4, r1c1.clone())],
+		)));
+
+		let weighted_input = vec![vec![r0c0, r0c1], vec![r1c0, r1c1]];
+		assert_eq!(
+			try_min_path(&weighted_input),
+			Ok(Some(Path { cost: 6, nodes: vec![(0, 1), (1, 1)], edges: vec![1] }))
+		);
+	}
 }
@@ -0,0 +1,281 @@
+use std::{cell::RefCell, collections::HashMap, ops::Range, rc::Rc};
+
+use crate::{Edge, LayeredGraph, MinPathError, Node, NodePointer, Weight};
+
+/// Dense min-plus (tropical) matrix of the least path costs from the nodes of
+/// one row to the nodes of a later row.
+///
+/// Entry `(i, j)` is the cost from column `i` to column `j`, `None` (the
+/// semiring's zero, standing for an infinite cost) if there is no path. The
+/// product of the transition matrices of consecutive rows is the transition
+/// matrix of the whole segment, so segments can be composed, cached and reused.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionMatrix<W = usize> {
+	rows: usize,
+	columns: usize,
+	/// Entries in row major order.
+	entries: Vec<Option<W>>,
+}
+
+impl<W: Weight> TransitionMatrix<W> {
+	/// Matrix without any path, the additive identity.
+	pub fn zero(rows: usize, columns: usize) -> Self {
+		TransitionMatrix { rows, columns, entries: vec![None; rows * columns] }
+	}
+
+	/// Matrix with free paths from each column to itself only, the
+	/// multiplicative identity.
+	pub fn identity(size: usize) -> Self {
+		let mut matrix = TransitionMatrix::zero(size, size);
+		for idx in 0..size {
+			matrix.set(idx, idx, Some(W::zero()));
+		}
+		matrix
+	}
+
+	/// Number of nodes in the row paths start at.
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	/// Number of nodes in the row paths end at.
+	pub fn columns(&self) -> usize {
+		self.columns
+	}
+
+	/// Cost from column `from` to column `to`, `None` if there is no path.
+	///
+	/// # Panics
+	///
+	/// Panics if either index is out of bounds.
+	pub fn get(&self, from: usize, to: usize) -> Option<W> {
+		assert!(from < self.rows && to < self.columns, "entry out of bounds");
+		self.entries[from * self.columns + to]
+	}
+
+	/// Sets the cost from column `from` to column `to`.
+	///
+	/// # Panics
+	///
+	/// Panics if either index is out of bounds.
+	pub fn set(&mut self, from: usize, to: usize, cost: Option<W>) {
+		assert!(from < self.rows && to < self.columns, "entry out of bounds");
+		self.entries[from * self.columns + to] = cost;
+	}
+
+	/// Keeps the cheaper of the current cost from `from` to `to` and `cost`.
+	fn relax(&mut self, from: usize, to: usize, cost: W) {
+		let entry = &mut self.entries[from * self.columns + to];
+		match entry {
+			Some(current) if *current <= cost => (),
+			_ => *entry = Some(cost),
+		}
+	}
+
+	/// Min-plus product: the least costs of going through `self`, then `rhs`.
+	///
+	/// Reports `MinPathError::EntryOverflow` with the `row` and `column` of the
+	/// product entry whose cost overflows.
+	///
+	/// # Panics
+	///
+	/// Panics if `self` does not have as many columns as `rhs` has rows.
+	pub fn multiply(&self, rhs: &TransitionMatrix<W>) -> Result<Self, MinPathError> {
+		assert_eq!(self.columns, rhs.rows, "matrix dimensions do not match");
+		let mut product = TransitionMatrix::zero(self.rows, rhs.columns);
+		for from in 0..self.rows {
+			for via in 0..self.columns {
+				let first = match self.get(from, via) {
+					Some(first) => first,
+					None => continue,
+				};
+				for to in 0..rhs.columns {
+					if let Some(second) = rhs.get(via, to) {
+						let cost = first
+							.checked_add(second)
+							.ok_or(MinPathError::EntryOverflow { row: from, column: to })?;
+						product.relax(from, to, cost);
+					}
+				}
+			}
+		}
+
+		Ok(product)
+	}
+
+	/// Transition matrix of the edges from the `from` nodes to the `to` nodes,
+	/// keeping the lightest of parallel edges. Like
+	/// `LayeredGraph::transition_matrix`, costs include the weight of the `to`
+	/// node an edge leads to, but not of the `from` node it leaves.
+	///
+	/// Nodes are located in errors as if `from` and `to` were rows 0 and 1 of
+	/// an input, and edges must all lead to one of the `to` nodes.
+	pub fn from_nodes(
+		from: &[NodePointer<W>],
+		to: &[NodePointer<W>],
+	) -> Result<Self, MinPathError> {
+		let columns: HashMap<*const RefCell<Node<W>>, usize> =
+			to.iter().enumerate().map(|(col_idx, node)| (Rc::as_ptr(node), col_idx)).collect();
+		let mut node_weights = Vec::with_capacity(to.len());
+		for (col_idx, node) in to.iter().enumerate() {
+			let node_weight = node.borrow().weight;
+			if node_weight.is_some_and(|weight| !weight.is_valid()) {
+				return Err(MinPathError::InvalidCost { row: 1, column: col_idx });
+			}
+			node_weights.push(node_weight);
+		}
+
+		let mut matrix = TransitionMatrix::zero(from.len(), to.len());
+		for (col_idx, node) in from.iter().enumerate() {
+			for (edge_idx, edge) in node.borrow().edges.iter().enumerate() {
+				let dest_col_idx = match columns.get(&Rc::as_ptr(&edge.destination)) {
+					Some(&dest_col_idx) => dest_col_idx,
+					None => {
+						return Err(MinPathError::DanglingDestination {
+							row: 0,
+							column: col_idx,
+							edge: edge_idx,
+						})
+					}
+				};
+				if !edge.weight.is_valid() {
+					return Err(MinPathError::InvalidWeight {
+						row: 0,
+						column: col_idx,
+						edge: edge_idx,
+					});
+				}
+				let cost = match node_weights[dest_col_idx] {
+					Some(weight) => edge
+						.weight
+						.checked_add(weight)
+						.ok_or(MinPathError::Overflow { row: 1, column: dest_col_idx })?,
+					None => edge.weight,
+				};
+				matrix.relax(col_idx, dest_col_idx, cost);
+			}
+		}
+
+		Ok(matrix)
+	}
+
+	/// Two new rows of nodes with an edge for each entry that has a path.
+	pub fn to_nodes(&self) -> (Vec<NodePointer<W>>, Vec<NodePointer<W>>) {
+		let to: Vec<NodePointer<W>> =
+			(0..self.columns).map(|_| Rc::new(RefCell::new(Node::new(Vec::new())))).collect();
+		let from = (0..self.rows)
+			.map(|from| {
+				let edges = (0..self.columns)
+					.filter_map(|col_idx| {
+						self.get(from, col_idx).map(|cost| Edge::new(cost, to[col_idx].clone()))
+					})
+					.collect();
+				Rc::new(RefCell::new(Node::new(edges)))
+			})
+			.collect();
+
+		(from, to)
+	}
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Transition matrix of the edges from the given row to the next one. Costs
+	/// include the weight of the node an edge leads to, but not of the one it
+	/// leaves.
+	///
+	/// # Panics
+	///
+	/// Panics if the row is the last one.
+	pub fn transition_matrix(&self, row_idx: usize) -> Result<TransitionMatrix<W>, MinPathError> {
+		let (from, to) = (self.row(row_idx), self.row(row_idx + 1));
+		let mut matrix = TransitionMatrix::zero(from.len(), to.len());
+		for node in from.clone() {
+			for edge in self.edges(node) {
+				let dest = self.destination(edge);
+				let cost = self.visit(dest, self.weight(edge), W::checked_add)?;
+				matrix.relax(node - from.start, dest - to.start, cost);
+			}
+		}
+
+		Ok(matrix)
+	}
+
+	/// Transition matrix from row `rows.start` to row `rows.end`, the identity
+	/// for an empty range. Like `transition_matrix`, the weights of the nodes
+	/// of row `rows.start` are not included.
+	///
+	/// # Panics
+	///
+	/// Panics if the range goes past the last row.
+	pub fn segment(&self, rows: Range<usize>) -> Result<TransitionMatrix<W>, MinPathError> {
+		let identity = TransitionMatrix::identity(self.row(rows.start).len());
+		rows.into_iter().try_fold(identity, |product, row_idx| {
+			product.multiply(&self.transition_matrix(row_idx)?)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::convert::TryFrom;
+
+	use super::*;
+
+	#[test]
+	fn segments_match_all_pairs() {
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, 2), (1, 3)], vec![(0, 0), (1, 1)], vec![(2, 7)]],
+			vec![vec![(0, 6)], vec![(0, 4), (1, 5)], vec![(2, 1)]],
+			vec![vec![], vec![], vec![]],
+		])
+		.unwrap();
+
+		let table = graph.all_pairs().unwrap();
+		let first = graph.segment(0..1).unwrap();
+		let whole = graph.segment(0..2).unwrap();
+		assert_eq!(whole, first.multiply(&graph.transition_matrix(1).unwrap()).unwrap());
+		assert_eq!(whole, TransitionMatrix::identity(3).multiply(&whole).unwrap());
+		for source in 0..3 {
+			for target in 0..3 {
+				assert_eq!(whole.get(source, target), table.cost(source, target));
+			}
+		}
+
+		let (from, to) = first.to_nodes();
+		assert_eq!(TransitionMatrix::from_nodes(&from, &to), Ok(first));
+	}
+
+	#[test]
+	fn from_nodes_matches_transition_matrix() {
+		let r1c0 = Rc::new(RefCell::new(Node::with_weight(10, vec![])));
+		let r1c1 = Rc::new(RefCell::new(Node::new(vec![])));
+		let r0c0 = Rc::new(RefCell::new(Node::with_weight(5, vec![Edge::new(1, r1c0.clone())])));
+		let r0c1 = Rc::new(RefCell::new(Node::new(vec![
+			Edge::new(2, r1c0.clone()),
+			Edge::new(3, r1c1.clone()),
+		])));
+		let (from, to) = (vec![r0c0, r0c1], vec![r1c0, r1c1]);
+		let graph = LayeredGraph::try_from(&vec![from.clone(), to.clone()]).unwrap();
+
+		let matrix = TransitionMatrix::from_nodes(&from, &to).unwrap();
+		assert_eq!(matrix.get(0, 0), Some(11));
+		assert_eq!(matrix, graph.transition_matrix(0).unwrap());
+
+		let invalid = vec![Rc::new(RefCell::new(Node::with_weight(f64::NAN, vec![])))];
+		let from = vec![Rc::new(RefCell::new(Node::new(vec![Edge::new(1.0, invalid[0].clone())])))];
+		assert_eq!(
+			TransitionMatrix::from_nodes(&from, &invalid),
+			Err(MinPathError::InvalidCost { row: 1, column: 0 })
+		);
+	}
+
+	#[test]
+	fn reports_overflowing_entry() {
+		let mut first = TransitionMatrix::<u8>::zero(2, 1);
+		first.set(1, 0, Some(200));
+		let mut second = TransitionMatrix::<u8>::zero(1, 2);
+		second.set(0, 0, Some(1));
+		second.set(0, 1, Some(100));
+		assert_eq!(first.multiply(&second), Err(MinPathError::EntryOverflow { row: 1, column: 1 }));
+	}
+}
@@ -0,0 +1,292 @@
+use std::{fmt, ops::AddAssign, slice};
+
+use crate::{layered::Objective, LayeredGraph, MinPathError, Path, Weight};
+
+/// Unbounded count of paths, graphs with many tied paths easily have more than
+/// fit in a `u128`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathCount {
+	/// Base 2^32 digits, least significant first, without trailing zeros.
+	digits: Vec<u32>,
+}
+
+impl PathCount {
+	/// The count as a `u128`, `None` if it does not fit.
+	pub fn to_u128(&self) -> Option<u128> {
+		if self.digits.len() > 4 {
+			return None;
+		}
+		Some(self.digits.iter().rev().fold(0, |value, &digit| value << 32 | digit as u128))
+	}
+}
+
+impl From<u64> for PathCount {
+	fn from(value: u64) -> Self {
+		let mut digits = vec![value as u32, (value >> 32) as u32];
+		while digits.last() == Some(&0) {
+			digits.pop();
+		}
+		PathCount { digits }
+	}
+}
+
+impl AddAssign<&PathCount> for PathCount {
+	fn add_assign(&mut self, other: &PathCount) {
+		let mut carry = 0;
+		for idx in 0..self.digits.len().max(other.digits.len()) {
+			let sum = *self.digits.get(idx).unwrap_or(&0) as u64
+				+ *other.digits.get(idx).unwrap_or(&0) as u64
+				+ carry;
+			match self.digits.get_mut(idx) {
+				Some(digit) => *digit = sum as u32,
+				None => self.digits.push(sum as u32),
+			}
+			carry = sum >> 32;
+		}
+		if carry > 0 {
+			self.digits.push(carry as u32);
+		}
+	}
+}
+
+impl fmt::Display for PathCount {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		const CHUNK: u64 = 1_000_000_000;
+
+		// Repeatedly divide by 10^9, collecting the remainders as 9 decimal digit chunks.
+		let mut digits = self.digits.clone();
+		let mut chunks = Vec::new();
+		while !digits.is_empty() {
+			let mut remainder = 0;
+			for digit in digits.iter_mut().rev() {
+				let value = remainder << 32 | *digit as u64;
+				*digit = (value / CHUNK) as u32;
+				remainder = value % CHUNK;
+			}
+			chunks.push(remainder);
+			while digits.last() == Some(&0) {
+				digits.pop();
+			}
+		}
+
+		match chunks.pop() {
+			None => write!(f, "0"),
+			Some(first) => {
+				write!(f, "{}", first)?;
+				chunks.iter().rev().try_for_each(|chunk| write!(f, "{:09}", chunk))
+			}
+		}
+	}
+}
+
+/// All least cost paths from row 0 to the last row of a graph.
+pub struct OptimalPaths<'a, W> {
+	graph: &'a LayeredGraph<W>,
+	/// Weight shared by all the paths.
+	cost: W,
+	/// Number of paths.
+	count: PathCount,
+	/// Previous nodes and the edges taken from them on the least cost paths to
+	/// each node.
+	predecessors: Vec<Vec<(usize, usize)>>,
+	/// Nodes of the last row the paths end at.
+	targets: Vec<usize>,
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Finds all the least cost paths from row 0 to the last row, `None` if
+	/// there is no path. Paths are counted without enumerating them, use
+	/// `OptimalPaths::iter` to go through them.
+	///
+	/// Paths that overflow are dropped as in `min_path`.
+	pub fn optimal_paths(&self) -> Result<Option<OptimalPaths<'_, W>>, MinPathError> {
+		if self.row_count() < 2 {
+			return Ok(None);
+		}
+		let last_row = self.row_count() - 1;
+		let drops_overflows = self.drops_overflows(Objective::Minimize);
+
+		// `costs[n]` is `None` while node `n` is inaccessible, and `overflows[n]`
+		// the error of the first path to it that overflowed.
+		let mut costs: Vec<Option<W>> = vec![None; self.node_count()];
+		let mut overflows: Vec<Option<MinPathError>> = vec![None; self.node_count()];
+		let mut counts: Vec<PathCount> = vec![PathCount::default(); self.node_count()];
+		let mut predecessors: Vec<Vec<(usize, usize)>> = vec![Vec::new(); self.node_count()];
+		for node in self.row(0) {
+			match self.visit(node, W::zero(), W::checked_add) {
+				Ok(cost) => costs[node] = Some(cost),
+				Err(err) if drops_overflows => overflows[node] = Some(err),
+				Err(err) => return Err(err),
+			}
+			counts[node] = PathCount::from(1);
+		}
+
+		for node in 0..self.row(last_row).start {
+			let src_cost = match costs[node] {
+				Some(cost) => cost,
+				None => {
+					if let Some(err) = overflows[node].clone() {
+						for edge in self.edges(node) {
+							overflows[self.destination(edge)].get_or_insert_with(|| err.clone());
+						}
+					}
+					continue;
+				}
+			};
+
+			for edge in self.edges(node) {
+				let dest = self.destination(edge);
+				let cost_to_dest = match src_cost.checked_add(self.weight(edge)) {
+					Some(cost_to_dest) => self.visit(dest, cost_to_dest, W::checked_add),
+					None => Err(self.overflow(dest)),
+				};
+				let cost_to_dest = match cost_to_dest {
+					Ok(cost_to_dest) => cost_to_dest,
+					Err(err) if drops_overflows => {
+						overflows[dest].get_or_insert(err);
+						continue;
+					}
+					Err(err) => return Err(err),
+				};
+
+				match costs[dest] {
+					Some(dest_cost) if dest_cost < cost_to_dest => continue,
+					Some(dest_cost) if dest_cost == cost_to_dest => (),
+					_ => {
+						costs[dest] = Some(cost_to_dest);
+						counts[dest] = PathCount::default();
+						predecessors[dest].clear();
+					}
+				}
+				// Edges only lead to later rows, so `node < dest`.
+				let (done, pending) = counts.split_at_mut(dest);
+				pending[0] += &done[node];
+				predecessors[dest].push((node, edge));
+			}
+		}
+
+		let maybe_cost =
+			self.row(last_row)
+				.filter_map(|node| costs[node])
+				.reduce(|a, b| if b < a { b } else { a });
+		let cost = match maybe_cost {
+			Some(cost) => cost,
+			None => {
+				return self
+					.row(last_row)
+					.find_map(|node| overflows[node].clone())
+					.map_or(Ok(None), Err)
+			}
+		};
+		let targets: Vec<usize> =
+			self.row(last_row).filter(|&node| costs[node] == Some(cost)).collect();
+		let mut count = PathCount::default();
+		for &target in targets.iter() {
+			count += &counts[target];
+		}
+
+		Ok(Some(OptimalPaths { graph: self, cost, count, predecessors, targets }))
+	}
+}
+
+impl<'a, W: Weight> OptimalPaths<'a, W> {
+	/// Weight shared by all the paths.
+	pub fn cost(&self) -> W {
+		self.cost
+	}
+
+	/// Number of paths, more than one if the optimum is ambiguous.
+	pub fn count(&self) -> &PathCount {
+		&self.count
+	}
+
+	/// Iterates over the paths, one at a time.
+	pub fn iter(&self) -> OptimalPathIter<'_, W> {
+		OptimalPathIter { paths: self, targets: self.targets.iter(), stack: Vec::new() }
+	}
+}
+
+/// Iterator over `OptimalPaths`.
+pub struct OptimalPathIter<'a, W> {
+	paths: &'a OptimalPaths<'a, W>,
+	/// Targets whose paths are yet to be gone through.
+	targets: slice::Iter<'a, usize>,
+	/// Nodes of the current path, from its target back to row 0, with the index
+	/// of the predecessor taken out of each.
+	stack: Vec<(usize, usize)>,
+}
+
+impl<'a, W: Weight> Iterator for OptimalPathIter<'a, W> {
+	type Item = Path<W>;
+
+	fn next(&mut self) -> Option<Path<W>> {
+		let predecessors = &self.paths.predecessors;
+
+		// Move on from the previous path to the next choice of predecessor.
+		while let Some((node, choice)) = self.stack.last_mut() {
+			*choice += 1;
+			if *choice < predecessors[*node].len() {
+				break;
+			}
+			self.stack.pop();
+		}
+		if self.stack.is_empty() {
+			self.stack.push((*self.targets.next()?, 0));
+		}
+
+		// Follow the chosen predecessors back to row 0.
+		while let Some(&(node, choice)) = self.stack.last() {
+			match predecessors[node].get(choice) {
+				Some(&(pred, _)) => self.stack.push((pred, 0)),
+				None => break,
+			}
+		}
+
+		let graph = self.paths.graph;
+		let nodes = self.stack.iter().rev().map(|&(node, _)| graph.position(node)).collect();
+		let edges = self.stack[..self.stack.len() - 1]
+			.iter()
+			.rev()
+			.map(|&(node, choice)| {
+				let (pred, edge) = predecessors[node][choice];
+				edge - graph.edges(pred).start
+			})
+			.collect();
+
+		Some(Path { cost: self.paths.cost, nodes, edges })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn counts_and_iterates_ties() {
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, 1), (1, 2)], vec![(0, 2), (1, 1)]],
+			vec![vec![(0, 0)], vec![(0, 0)]],
+			vec![vec![]],
+		])
+		.unwrap();
+		let paths = graph.optimal_paths().unwrap().unwrap();
+		assert_eq!((paths.cost(), paths.count().to_u128()), (1, Some(2)));
+
+		let nodes: Vec<Vec<(usize, usize)>> = paths.iter().map(|path| path.nodes).collect();
+		assert_eq!(nodes, vec![vec![(0, 0), (1, 0), (2, 0)], vec![(0, 1), (1, 1), (2, 0)]]);
+		assert!(paths.iter().all(|path| graph.k_shortest_paths(2).unwrap().contains(&path)));
+	}
+
+	#[test]
+	fn counts_beyond_u128() {
+		// Every one of the 4^70 paths through a complete graph of 70 rows costs 0.
+		let row = vec![vec![(0, 0), (1, 0), (2, 0), (3, 0)]; 4];
+		let mut rows = vec![row; 69];
+		rows.push(vec![vec![]; 4]);
+		let graph = LayeredGraph::from_rows(&rows).unwrap();
+
+		let count = graph.optimal_paths().unwrap().unwrap().count().clone();
+		assert_eq!(count.to_u128(), None);
+		assert_eq!(count.to_string(), "1393796574908163946345982392040522594123776");
+	}
+}
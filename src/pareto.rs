@@ -0,0 +1,114 @@
+use std::cmp::Ordering;
+
+use crate::{constrained::insert_label, LayeredGraph, MinPathError, Path, Weight};
+
+/// Path of a Pareto front, along with the resources it consumes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParetoPath<W = usize> {
+	pub path: Path<W>,
+	/// Amount of each resource consumed along the path.
+	pub resources: Vec<W>,
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Finds the paths from row 0 to the last row that no other path beats on
+	/// both cost and every resource, cheapest first. Of paths with the same cost
+	/// and resources, only the first one found is kept.
+	///
+	/// The front can grow exponentially with the number of rows, so paths can
+	/// also be left out of it when a returned one is worse by at most
+	/// `epsilon`, whose first entry is the slack on the cost and the next ones
+	/// the slack on each resource. Missing entries are 0, so `&[]` gives the
+	/// exact front. The slack is only applied to the paths to the last row, as
+	/// applying it at every row would add it up along the way.
+	pub fn pareto_paths(&self, epsilon: &[W]) -> Result<Vec<ParetoPath<W>>, MinPathError> {
+		let (mut arena, mut labels) = self.resource_sweep(|_| true)?;
+
+		// Paths to different nodes of the last row can dominate each other too.
+		let mut front = Vec::new();
+		for node in self.last_row() {
+			for label_idx in std::mem::take(&mut labels[node]) {
+				let label = arena[label_idx].clone();
+				insert_label(&mut arena, &mut front, label, epsilon);
+			}
+		}
+		// The sort is stable, so ties keep the order paths were found in.
+		front
+			.sort_by(|&a, &b| arena[a].cost.partial_cmp(&arena[b].cost).unwrap_or(Ordering::Equal));
+
+		Ok(front
+			.into_iter()
+			.map(|label_idx| ParetoPath {
+				path: self.trace_resource_label(&arena, label_idx),
+				resources: arena[label_idx].resources.clone(),
+			})
+			.collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{cell::RefCell, convert::TryFrom, rc::Rc};
+
+	use super::*;
+	use crate::{Edge, Node};
+
+	#[test]
+	fn finds_pareto_front() {
+		let r2c0 = Rc::new(RefCell::new(Node::new(vec![])));
+		let r1 = [(1, 5), (2, 1), (1, 3), (1, 5)]
+			.iter()
+			.map(|&(weight, resource)| {
+				Rc::new(RefCell::new(Node::new(vec![Edge::with_resources(
+					weight,
+					vec![resource],
+					r2c0.clone(),
+				)])))
+			})
+			.collect::<Vec<_>>();
+		let r0c0 = Rc::new(RefCell::new(Node::new(
+			[(1, 5), (3, 1), (2, 3), (3, 6)]
+				.iter()
+				.zip(r1.iter())
+				.map(|(&(weight, resource), dest)| {
+					Edge::with_resources(weight, vec![resource], dest.clone())
+				})
+				.collect(),
+		)));
+		let graph = LayeredGraph::try_from(&vec![vec![r0c0], r1, vec![r2c0]]).unwrap();
+
+		let front = graph.pareto_paths(&[]).unwrap();
+		let objectives: Vec<(usize, Vec<usize>)> =
+			front.iter().map(|p| (p.path.cost, p.resources.clone())).collect();
+		assert_eq!(objectives, vec![(2, vec![10]), (3, vec![6]), (5, vec![2])]);
+		assert_eq!(front[1].path.nodes, vec![(0, 0), (1, 2), (2, 0)]);
+
+		// With a slack of 4 on the resource, the path costing 2 is close enough
+		// to the one costing 3.
+		let coarse_front = graph.pareto_paths(&[0, 4]).unwrap();
+		let costs: Vec<usize> = coarse_front.iter().map(|p| p.path.cost).collect();
+		assert_eq!(costs, vec![2, 5]);
+	}
+
+	#[test]
+	fn slack_does_not_add_up_over_rows() {
+		let edges = |dest: &Rc<RefCell<Node>>| {
+			vec![
+				Edge::with_resources(0, vec![1], dest.clone()),
+				Edge::with_resources(1, vec![0], dest.clone()),
+			]
+		};
+		let r2c0 = Rc::new(RefCell::new(Node::new(vec![])));
+		let r1c0 = Rc::new(RefCell::new(Node::new(edges(&r2c0))));
+		let r0c0 = Rc::new(RefCell::new(Node::new(edges(&r1c0))));
+		let graph = LayeredGraph::try_from(&vec![vec![r0c0], vec![r1c0], vec![r2c0]]).unwrap();
+
+		let objectives = |epsilon: &[usize]| {
+			let front = graph.pareto_paths(epsilon).unwrap();
+			front.into_iter().map(|p| (p.path.cost, p.resources)).collect::<Vec<_>>()
+		};
+		assert_eq!(objectives(&[]), vec![(0, vec![2]), (1, vec![1]), (2, vec![0])]);
+		// The path costing 2 uses 2 less of the resource than the one costing 0.
+		assert_eq!(objectives(&[0, 1]), vec![(0, vec![2]), (2, vec![0])]);
+	}
+}
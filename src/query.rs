@@ -0,0 +1,140 @@
+use crate::{layered::Objective, LayeredGraph, MinPathError, TieBreak, Weight};
+
+/// Where paths through a `LayeredGraph` may start and end, and what they cost
+/// to start and end there.
+///
+/// By default paths start at any node of row 0 and end at any node of the last
+/// row at no extra cost, like `min_path_cost`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query<W = usize> {
+	/// Columns of row 0 paths may start at, with the initial cost of starting
+	/// there. Empty for all of row 0.
+	sources: Vec<(usize, W)>,
+	/// Columns of the last row paths may end at, with the terminal cost of
+	/// ending there. Empty for all of the last row.
+	targets: Vec<(usize, W)>,
+	pub(crate) objective: Objective,
+	/// Secondary objectives for paths of the same cost, in order.
+	pub(crate) tie_breaks: Vec<TieBreak>,
+}
+
+impl<W: Weight> Query<W> {
+	/// Query for the least cost path from any node of row 0 to any node of the
+	/// last row.
+	pub fn new() -> Self {
+		Query {
+			sources: Vec::new(),
+			targets: Vec::new(),
+			objective: Objective::Minimize,
+			tie_breaks: Vec::new(),
+		}
+	}
+
+	/// Allows paths to start at the given column of row 0.
+	pub fn source(self, column: usize) -> Self {
+		self.source_with_cost(column, W::zero())
+	}
+
+	/// Allows paths to start at the given column of row 0, adding `cost` to them.
+	pub fn source_with_cost(mut self, column: usize, cost: W) -> Self {
+		self.sources.push((column, cost));
+		self
+	}
+
+	/// Allows paths to end at the given column of the last row.
+	pub fn target(self, column: usize) -> Self {
+		self.target_with_cost(column, W::zero())
+	}
+
+	/// Allows paths to end at the given column of the last row, adding `cost`
+	/// to them.
+	pub fn target_with_cost(mut self, column: usize, cost: W) -> Self {
+		self.targets.push((column, cost));
+		self
+	}
+
+	/// Looks for the maximum cost path instead of the least cost one.
+	pub fn maximize(mut self) -> Self {
+		self.objective = Objective::Maximize;
+		self
+	}
+
+	/// Decides between paths of the same cost, and of the same outcome for the
+	/// previous tie breaks, with `tie_break`. Once any is given, paths still
+	/// tied after all of them are decided by `TieBreak::Leftmost`, so the path
+	/// found only depends on the graph and not on the order it is swept in.
+	pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+		self.tie_breaks.push(tie_break);
+		self
+	}
+
+	/// `(node, cost)` of the sources and targets in the given graph.
+	#[allow(clippy::type_complexity)]
+	pub(crate) fn nodes(
+		&self,
+		graph: &LayeredGraph<W>,
+	) -> Result<(Vec<(usize, W)>, Vec<(usize, W)>), MinPathError> {
+		let last_row = graph.row_count() - 1;
+		Ok((nodes_in_row(graph, 0, &self.sources)?, nodes_in_row(graph, last_row, &self.targets)?))
+	}
+}
+
+impl<W: Weight> Default for Query<W> {
+	fn default() -> Self {
+		Query::new()
+	}
+}
+
+/// `(node, cost)` of the given `(column, cost)` pairs of a row, or of all its
+/// nodes at no cost if there are none.
+fn nodes_in_row<W: Weight>(
+	graph: &LayeredGraph<W>,
+	row_idx: usize,
+	columns: &[(usize, W)],
+) -> Result<Vec<(usize, W)>, MinPathError> {
+	let row = graph.row(row_idx);
+	if columns.is_empty() {
+		return Ok(row.map(|node| (node, W::zero())).collect());
+	}
+
+	columns
+		.iter()
+		.map(|&(column, cost)| {
+			if column >= row.len() {
+				Err(MinPathError::InvalidColumn { row: row_idx, column })
+			} else if !cost.is_valid() {
+				Err(MinPathError::InvalidCost { row: row_idx, column })
+			} else {
+				Ok((row.start + column, cost))
+			}
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::layered::example_graph;
+	use crate::Path;
+
+	#[test]
+	fn restricts_sources_and_targets() {
+		let graph = example_graph();
+
+		assert_eq!(graph.query(&Query::new()), graph.min_path());
+		assert_eq!(
+			graph.query(&Query::new().source(0).target(1)),
+			Ok(Some(Path { cost: 8, nodes: vec![(0, 0), (1, 1), (2, 1)], edges: vec![1, 1] }))
+		);
+		// Starting at column 1 costs too much to be worth it.
+		let query = Query::new().source(0).source_with_cost(1, 3).target_with_cost(0, 10);
+		assert_eq!(
+			graph.query(&query).unwrap().map(|path| (path.cost, path.nodes[0])),
+			Some((17, (0, 0)))
+		);
+		assert_eq!(
+			graph.query(&Query::new().target(2)),
+			Err(MinPathError::InvalidColumn { row: 2, column: 2 })
+		);
+	}
+}
@@ -0,0 +1,96 @@
+use crate::{Extended, LayeredGraph, MaxPlus, MinPathError, Path};
+
+impl LayeredGraph<f64> {
+	/// Finds the path from row 0 to the last row whose edges and nodes, weighted
+	/// with their probability of success, are the most likely to all succeed,
+	/// `None` if there is no path with a non-zero probability. The path's cost
+	/// is that probability.
+	///
+	/// Probabilities are multiplied as sums of their logarithms, so paths are
+	/// compared correctly even when their probability is too small for an `f64`
+	/// and is returned as 0, see `most_reliable_path_ln` for the logarithm.
+	/// Reports `MinPathError::InvalidProbability` for the first edge whose
+	/// weight is outside of `[0, 1]`, and `MinPathError::InvalidCost` for such
+	/// a node.
+	pub fn most_reliable_path(&self) -> Result<Option<Path<f64>>, MinPathError> {
+		Ok(self.most_reliable_path_ln()?.map(|path| Path { cost: path.cost.exp(), ..path }))
+	}
+
+	/// Same as `most_reliable_path`, but the path's cost is the natural
+	/// logarithm of its probability, which stays comparable however long the
+	/// path is.
+	pub fn most_reliable_path_ln(&self) -> Result<Option<Path<f64>>, MinPathError> {
+		for node in 0..self.node_count() {
+			if self.node_weight(node).is_some_and(|weight| !(0.0..=1.0).contains(&weight)) {
+				let (row, column) = self.position(node);
+				return Err(MinPathError::InvalidCost { row, column });
+			}
+			for edge in self.edges(node) {
+				if !(0.0..=1.0).contains(&self.weight(edge)) {
+					let (row, column) = self.position(node);
+					return Err(MinPathError::InvalidProbability {
+						row,
+						column,
+						edge: edge - self.edges(node).start,
+					});
+				}
+			}
+		}
+
+		let path = self.semiring_path(|probability| {
+			if probability == 0.0 {
+				// The logarithm of 0 is negative infinity, the path can not succeed.
+				MaxPlus(Extended::NegInfinity)
+			} else {
+				MaxPlus(Extended::Finite(probability.ln()))
+			}
+		});
+
+		Ok(path.and_then(|path| {
+			let log_probability = path.cost.0.finite()?;
+			Some(Path { cost: log_probability, nodes: path.nodes, edges: path.edges })
+		}))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn finds_most_reliable_path() {
+		let graph = LayeredGraph::from_rows(&[
+			vec![vec![(0, 0.9), (1, 0.5)], vec![(1, 1.0)]],
+			vec![vec![(0, 0.5)], vec![(0, 0.8), (1, 0.0)]],
+			vec![vec![], vec![]],
+		])
+		.unwrap();
+
+		let path = graph.most_reliable_path().unwrap().unwrap();
+		assert_eq!((path.nodes, path.edges), (vec![(0, 1), (1, 1), (2, 0)], vec![0, 0]));
+		assert!((path.cost - 0.8).abs() < 1e-12);
+
+		let impossible = LayeredGraph::from_rows(&[vec![vec![(0, 0.0)]], vec![vec![]]]).unwrap();
+		assert_eq!(impossible.most_reliable_path(), Ok(None));
+
+		let invalid =
+			LayeredGraph::from_rows(&[vec![vec![(0, 0.5), (0, 1.5)]], vec![vec![]]]).unwrap();
+		assert_eq!(
+			invalid.most_reliable_path(),
+			Err(MinPathError::InvalidProbability { row: 0, column: 0, edge: 1 })
+		);
+	}
+
+	#[test]
+	fn long_paths_do_not_underflow() {
+		// 0.1^400 is far below the smallest positive `f64`, but its logarithm is not.
+		let mut rows = vec![vec![vec![(0, 0.1), (1, 0.1)], vec![(1, 0.05)]]; 400];
+		rows.push(vec![vec![], vec![]]);
+		let graph = LayeredGraph::from_rows(&rows).unwrap();
+
+		let path = graph.most_reliable_path_ln().unwrap().unwrap();
+		assert!(path.nodes.iter().all(|&(_, column)| column == 0));
+		assert!((path.cost - 400.0 * 0.1f64.ln()).abs() < 1e-9);
+		assert_eq!(graph.most_reliable_path().unwrap().map(|path| path.nodes), Some(path.nodes));
+	}
+}
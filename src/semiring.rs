@@ -0,0 +1,398 @@
+use crate::{layered::Label, LayeredGraph, Path, Weight};
+
+/// Semiring the row sweep can be run over: `add` combines the values of
+/// alternative paths and `mul` extends the value of a path with an edge.
+///
+/// Different semirings turn the same sweep into shortest paths (`MinPlus`),
+/// longest paths (`MaxPlus`), widest paths (`MaxMin`), minimax paths
+/// (`MinMax`), path counts or probability mass (`SumProduct`) and
+/// reachability (`Boolean`).
+pub trait Semiring: Copy + PartialEq {
+	/// Value of there being no path, the identity of `add` and annihilator of
+	/// `mul`.
+	fn zero() -> Self;
+
+	/// Value of the path without edges, the identity of `mul`.
+	fn one() -> Self;
+
+	/// Combines the values of two alternative paths.
+	fn add(&self, other: &Self) -> Self;
+
+	/// Extends the value of a path with another one.
+	fn mul(&self, other: &Self) -> Self;
+}
+
+/// Semiring whose `add` always returns one of its operands, so the path that
+/// value came from is well defined and can be traced back.
+pub trait Selective: Semiring {}
+
+/// Weight extended with infinities, the values of the `MinPlus`, `MaxPlus`,
+/// `MaxMin` and `MinMax` semirings. Ordered as
+/// `NegInfinity < Finite(_) < Infinity`.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Extended<W> {
+	NegInfinity,
+	Finite(W),
+	Infinity,
+}
+
+impl<W: Weight> Extended<W> {
+	/// The weight, `None` if it is infinite.
+	pub fn finite(self) -> Option<W> {
+		match self {
+			Extended::Finite(weight) => Some(weight),
+			_ => None,
+		}
+	}
+
+	/// Sum where an infinity wins over a finite weight, saturating on overflow.
+	fn saturating_add(self, other: Self) -> Self {
+		match (self, other) {
+			(Extended::Finite(a), Extended::Finite(b)) => Extended::Finite(a.saturating_add(b)),
+			(Extended::Finite(_), infinite) | (infinite, _) => infinite,
+		}
+	}
+
+	fn min(self, other: Self) -> Self {
+		if other < self {
+			other
+		} else {
+			self
+		}
+	}
+
+	fn max(self, other: Self) -> Self {
+		if other > self {
+			other
+		} else {
+			self
+		}
+	}
+}
+
+/// Least path cost, `Infinity` if there is no path. Costs saturate instead of
+/// overflowing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinPlus<W>(pub Extended<W>);
+
+impl<W: Weight> Semiring for MinPlus<W> {
+	fn zero() -> Self {
+		MinPlus(Extended::Infinity)
+	}
+
+	fn one() -> Self {
+		MinPlus(Extended::Finite(W::zero()))
+	}
+
+	fn add(&self, other: &Self) -> Self {
+		MinPlus(self.0.min(other.0))
+	}
+
+	fn mul(&self, other: &Self) -> Self {
+		MinPlus(self.0.saturating_add(other.0))
+	}
+}
+
+impl<W: Weight> Selective for MinPlus<W> {}
+
+/// Maximum path cost, `NegInfinity` if there is no path. Costs saturate
+/// instead of overflowing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MaxPlus<W>(pub Extended<W>);
+
+impl<W: Weight> Semiring for MaxPlus<W> {
+	fn zero() -> Self {
+		MaxPlus(Extended::NegInfinity)
+	}
+
+	fn one() -> Self {
+		MaxPlus(Extended::Finite(W::zero()))
+	}
+
+	fn add(&self, other: &Self) -> Self {
+		MaxPlus(self.0.max(other.0))
+	}
+
+	fn mul(&self, other: &Self) -> Self {
+		MaxPlus(self.0.saturating_add(other.0))
+	}
+}
+
+impl<W: Weight> Selective for MaxPlus<W> {}
+
+/// Widest path: the largest capacity, i.e. smallest edge weight, of any path.
+/// `NegInfinity` if there is no path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MaxMin<W>(pub Extended<W>);
+
+impl<W: Weight> Semiring for MaxMin<W> {
+	fn zero() -> Self {
+		MaxMin(Extended::NegInfinity)
+	}
+
+	fn one() -> Self {
+		MaxMin(Extended::Infinity)
+	}
+
+	fn add(&self, other: &Self) -> Self {
+		MaxMin(self.0.max(other.0))
+	}
+
+	fn mul(&self, other: &Self) -> Self {
+		MaxMin(self.0.min(other.0))
+	}
+}
+
+impl<W: Weight> Selective for MaxMin<W> {}
+
+/// Minimax path: the smallest largest edge weight of any path. `Infinity` if
+/// there is no path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinMax<W>(pub Extended<W>);
+
+impl<W: Weight> Semiring for MinMax<W> {
+	fn zero() -> Self {
+		MinMax(Extended::Infinity)
+	}
+
+	fn one() -> Self {
+		MinMax(Extended::NegInfinity)
+	}
+
+	fn add(&self, other: &Self) -> Self {
+		MinMax(self.0.min(other.0))
+	}
+
+	fn mul(&self, other: &Self) -> Self {
+		MinMax(self.0.max(other.0))
+	}
+}
+
+impl<W: Weight> Selective for MinMax<W> {}
+
+/// Sum over all paths of the product of their edge values, e.g. the number of
+/// paths with every edge worth 1, or the probability mass of reaching the last
+/// row with edges worth their probability. Integers saturate instead of
+/// overflowing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SumProduct<T>(pub T);
+
+macro_rules! impl_sum_product_for_integer {
+	($($t:ty),*) => {
+		$(
+			impl Semiring for SumProduct<$t> {
+				fn zero() -> Self {
+					SumProduct(0)
+				}
+
+				fn one() -> Self {
+					SumProduct(1)
+				}
+
+				fn add(&self, other: &Self) -> Self {
+					SumProduct(self.0.saturating_add(other.0))
+				}
+
+				fn mul(&self, other: &Self) -> Self {
+					SumProduct(self.0.saturating_mul(other.0))
+				}
+			}
+		)*
+	};
+}
+
+impl_sum_product_for_integer!(u32, u64, u128, usize);
+
+macro_rules! impl_sum_product_for_float {
+	($($t:ty),*) => {
+		$(
+			impl Semiring for SumProduct<$t> {
+				fn zero() -> Self {
+					SumProduct(0.0)
+				}
+
+				fn one() -> Self {
+					SumProduct(1.0)
+				}
+
+				fn add(&self, other: &Self) -> Self {
+					SumProduct(self.0 + other.0)
+				}
+
+				fn mul(&self, other: &Self) -> Self {
+					SumProduct(self.0 * other.0)
+				}
+			}
+		)*
+	};
+}
+
+impl_sum_product_for_float!(f32, f64);
+
+/// Whether there is any path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Boolean(pub bool);
+
+impl Semiring for Boolean {
+	fn zero() -> Self {
+		Boolean(false)
+	}
+
+	fn one() -> Self {
+		Boolean(true)
+	}
+
+	fn add(&self, other: &Self) -> Self {
+		Boolean(self.0 || other.0)
+	}
+
+	fn mul(&self, other: &Self) -> Self {
+		Boolean(self.0 && other.0)
+	}
+}
+
+impl Selective for Boolean {}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Combines, with `S::add`, the values of all paths from row 0 to the last
+	/// row, each being the product, with `S::mul`, of the `value`s of its edges
+	/// and of its nodes' weights. `S::zero` if there is no path.
+	pub fn semiring_sum<S: Semiring>(&self, value: impl Fn(W) -> S) -> S {
+		if self.row_count() < 2 {
+			return S::zero();
+		}
+
+		let mut sums = vec![S::zero(); self.node_count()];
+		for node in self.row(0) {
+			sums[node] = self.visit_value(node, S::one(), &value);
+		}
+		for node in 0..self.last_row().start {
+			if sums[node] == S::zero() {
+				// We are at an inaccessible node.
+				continue;
+			}
+			for edge in self.edges(node) {
+				let dest = self.destination(edge);
+				let path_value =
+					self.visit_value(dest, sums[node].mul(&value(self.weight(edge))), &value);
+				sums[dest] = sums[dest].add(&path_value);
+			}
+		}
+
+		self.last_row().fold(S::zero(), |sum, node| sum.add(&sums[node]))
+	}
+
+	/// Finds the path whose value `semiring_sum` picks, `None` if there is no
+	/// path. Of paths with the same value, the first one found is kept.
+	pub fn semiring_path<S: Selective>(&self, value: impl Fn(W) -> S) -> Option<Path<S>> {
+		self.selective_sweep(value, true)
+	}
+
+	/// Same as `semiring_path`, but the path's value only accounts for its
+	/// edges, not for its nodes' weights.
+	pub(crate) fn edge_semiring_path<S: Selective>(
+		&self,
+		value: impl Fn(W) -> S,
+	) -> Option<Path<S>> {
+		self.selective_sweep(value, false)
+	}
+
+	fn selective_sweep<S: Selective>(
+		&self,
+		value: impl Fn(W) -> S,
+		with_nodes: bool,
+	) -> Option<Path<S>> {
+		let visit = |node: usize, path_value: S| {
+			if with_nodes {
+				self.visit_value(node, path_value, &value)
+			} else {
+				path_value
+			}
+		};
+		if self.row_count() < 2 {
+			return None;
+		}
+
+		// `labels[n]` is `None` while node `n` is inaccessible.
+		let mut labels: Vec<Option<Label<S>>> = vec![None; self.node_count()];
+		for node in self.row(0) {
+			labels[node] = Some(Label { cost: visit(node, S::one()), predecessor: None });
+		}
+		for node in 0..self.last_row().start {
+			let src_value = match labels[node] {
+				Some(label) => label.cost,
+				None => continue,
+			};
+			for edge in self.edges(node) {
+				let dest = self.destination(edge);
+				let candidate = visit(dest, src_value.mul(&value(self.weight(edge))));
+				// `add` is selective, so the candidate either replaces the current value or not.
+				match labels[dest] {
+					Some(label) if label.cost.add(&candidate) == label.cost => (),
+					_ => {
+						labels[dest] =
+							Some(Label { cost: candidate, predecessor: Some((node, edge)) })
+					}
+				}
+			}
+		}
+
+		let mut final_path: Option<(S, usize)> = None;
+		for node in self.last_row() {
+			if let Some(label) = labels[node] {
+				match final_path {
+					Some((final_value, _)) if final_value.add(&label.cost) == final_value => (),
+					_ => final_path = Some((label.cost, node)),
+				}
+			}
+		}
+
+		final_path
+			.filter(|(value, _)| *value != S::zero())
+			.map(|(value, node)| self.trace(&labels, value, node))
+	}
+
+	/// Extends the value of a path reaching `node` with the value of the node's
+	/// weight, if it has one.
+	fn visit_value<S: Semiring>(&self, node: usize, path_value: S, value: impl Fn(W) -> S) -> S {
+		match self.node_weight(node) {
+			Some(weight) => path_value.mul(&value(weight)),
+			None => path_value,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::layered::example_graph;
+
+	#[test]
+	fn same_sweep_different_semirings() {
+		let graph = example_graph();
+
+		let shortest = graph.semiring_path(|weight| MinPlus(Extended::Finite(weight))).unwrap();
+		assert_eq!(
+			Some(shortest.cost.0.finite().unwrap()),
+			graph.min_path().unwrap().map(|path| path.cost)
+		);
+		assert_eq!(shortest.nodes, graph.min_path().unwrap().unwrap().nodes);
+
+		let longest = graph.semiring_sum(|weight| MaxPlus(Extended::Finite(weight)));
+		assert_eq!(longest.0.finite(), graph.max_path().unwrap().map(|path| path.cost));
+
+		let widest = graph.semiring_path(|weight| MaxMin(Extended::Finite(weight))).unwrap();
+		assert_eq!(
+			(widest.cost.0, widest.nodes),
+			(Extended::Finite(3), vec![(0, 0), (1, 1), (2, 0)])
+		);
+
+		assert_eq!(graph.semiring_sum(|_| SumProduct(1u64)), SumProduct(6));
+		assert_eq!(graph.semiring_sum(|_| SumProduct(0.5)), SumProduct(1.5));
+		assert_eq!(graph.semiring_sum(|_| Boolean(true)), Boolean(true));
+
+		let disconnected = LayeredGraph::<usize>::from_rows(&[vec![vec![]], vec![vec![]]]).unwrap();
+		assert_eq!(disconnected.semiring_sum(|_| Boolean(true)), Boolean(false));
+		assert_eq!(disconnected.semiring_path(|weight| MinPlus(Extended::Finite(weight))), None);
+	}
+}
@@ -0,0 +1,275 @@
+use std::cmp::Ordering;
+
+use crate::{
+	layered::{Label, Objective},
+	LayeredGraph, MinPathError, Path, Query, Weight,
+};
+
+/// Secondary objective deciding between paths of the same cost, see
+/// `Query::tie_break`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreak {
+	/// Fewest edges leading to another column than the one they leave.
+	ColumnChanges,
+	/// Least total amount of the given resource, see `Edge::with_resources`.
+	Resource(usize),
+	/// Path whose columns, read from row 0, come first in lexicographic order.
+	Leftmost,
+}
+
+/// Dynamic programming state of a reachable node, with what tie breaks need.
+#[derive(Clone)]
+struct TieLabel<W> {
+	cost: W,
+	column_changes: usize,
+	/// Resources consumed along the path.
+	resources: Vec<W>,
+	predecessor: Option<(usize, usize)>,
+}
+
+impl<W: Weight> LayeredGraph<W> {
+	/// Same as `query` with a non-empty `query.tie_breaks`: paths of the same
+	/// cost are compared by each tie break in turn, then by `TieBreak::Leftmost`,
+	/// so the path returned does not depend on the order nodes are stored in.
+	pub(crate) fn tie_broken_query(
+		&self,
+		query: &Query<W>,
+	) -> Result<Option<Path<W>>, MinPathError> {
+		let (sources, targets) = query.nodes(self)?;
+		if self.row_count() < 2 {
+			return Ok(None);
+		}
+		let better = |a: &TieLabel<W>, a_rank: usize, b: &TieLabel<W>, b_rank: usize| {
+			prefers(query.objective, &query.tie_breaks, (a, a_rank), (b, b_rank))
+		};
+
+		let drops_overflows = self.drops_overflows(query.objective);
+
+		// `overflows[n]` is the error of the first path to node `n` whose cost
+		// overflowed, which only matters if no other path reaches it.
+		let mut labels: Vec<Option<TieLabel<W>>> = vec![None; self.node_count()];
+		let mut overflows: Vec<Option<MinPathError>> = vec![None; self.node_count()];
+		for (node, cost) in sources {
+			let cost = match self.visit(node, cost, W::checked_add) {
+				Ok(cost) => cost,
+				Err(err) if drops_overflows => {
+					overflows[node].get_or_insert(err);
+					continue;
+				}
+				Err(err) => return Err(err),
+			};
+			let resources = vec![W::zero(); self.resource_count()];
+			let candidate = TieLabel { cost, column_changes: 0, resources, predecessor: None };
+			match &labels[node] {
+				Some(label) if !better(&candidate, 0, label, 0) => (),
+				_ => labels[node] = Some(candidate),
+			}
+		}
+
+		// `ranks[n]` orders the best path to node `n` among those to its row, as
+		// `TieBreak::Leftmost` would.
+		let mut ranks = vec![usize::MAX; self.node_count()];
+		for row_idx in 0..self.row_count() - 1 {
+			self.rank_row(row_idx, &labels, &mut ranks);
+			for node in self.row(row_idx) {
+				let label = match &labels[node] {
+					Some(label) => label.clone(),
+					None => {
+						if let Some(err) = overflows[node].clone() {
+							for edge in self.edges(node) {
+								overflows[self.destination(edge)]
+									.get_or_insert_with(|| err.clone());
+							}
+						}
+						continue;
+					}
+				};
+
+				for edge in self.edges(node) {
+					let dest = self.destination(edge);
+					let cost = match label.cost.checked_add(self.weight(edge)) {
+						Some(cost) => self.visit(dest, cost, W::checked_add),
+						None => Err(self.overflow(dest)),
+					};
+					let cost = match cost {
+						Ok(cost) => cost,
+						Err(err) if drops_overflows => {
+							overflows[dest].get_or_insert(err);
+							continue;
+						}
+						Err(err) => return Err(err),
+					};
+					let column_changes = label.column_changes
+						+ (self.position(dest).1 != self.position(node).1) as usize;
+					let resources = label
+						.resources
+						.iter()
+						.zip(self.resources(edge))
+						.map(|(&used, &consumed)| {
+							used.checked_add(consumed).ok_or_else(|| self.overflow(dest))
+						})
+						.collect::<Result<Vec<W>, MinPathError>>()?;
+
+					let candidate = TieLabel {
+						cost,
+						column_changes,
+						resources,
+						predecessor: Some((node, edge)),
+					};
+					let replaces = match &labels[dest] {
+						Some(current) => {
+							let current_rank = predecessor_rank(current, &ranks);
+							better(&candidate, ranks[node], current, current_rank)
+						}
+						None => true,
+					};
+					if replaces {
+						labels[dest] = Some(candidate);
+					}
+				}
+			}
+		}
+		self.rank_row(self.row_count() - 1, &labels, &mut ranks);
+
+		let mut final_path: Option<(TieLabel<W>, usize)> = None;
+		let mut maybe_overflow = None;
+		for (node, terminal_cost) in targets {
+			let candidate = match (&labels[node], &overflows[node]) {
+				(Some(label), _) => match label.cost.checked_add(terminal_cost) {
+					Some(cost) => Ok(TieLabel { cost, ..label.clone() }),
+					None => Err(self.overflow(node)),
+				},
+				(None, Some(err)) => Err(err.clone()),
+				(None, None) => continue,
+			};
+			let candidate = match candidate {
+				Ok(candidate) => candidate,
+				// A negative terminal cost could bring an overflowing path back in range.
+				Err(err) if drops_overflows && terminal_cost >= W::zero() => {
+					maybe_overflow.get_or_insert(err);
+					continue;
+				}
+				Err(err) => return Err(err),
+			};
+			let replaces = match &final_path {
+				Some((final_label, final_node)) => {
+					better(&candidate, ranks[node], final_label, ranks[*final_node])
+				}
+				None => true,
+			};
+			if replaces {
+				final_path = Some((candidate, node));
+			}
+		}
+		if let (None, Some(err)) = (&final_path, maybe_overflow) {
+			return Err(err);
+		}
+
+		let labels: Vec<Option<Label<W>>> = labels
+			.iter()
+			.map(|label| {
+				label
+					.as_ref()
+					.map(|label| Label { cost: label.cost, predecessor: label.predecessor })
+			})
+			.collect();
+		Ok(final_path.map(|(label, node)| self.trace(&labels, label.cost, node)))
+	}
+
+	/// Ranks the reachable nodes of the given row by the columns of their best
+	/// paths: those of the previous row's node, then their own.
+	fn rank_row(&self, row_idx: usize, labels: &[Option<TieLabel<W>>], ranks: &mut [usize]) {
+		let mut nodes: Vec<usize> =
+			self.row(row_idx).filter(|&node| labels[node].is_some()).collect();
+		nodes.sort_by_key(|&node| {
+			(labels[node].as_ref().map_or(0, |label| predecessor_rank(label, ranks)), node)
+		});
+		for (rank, node) in nodes.into_iter().enumerate() {
+			ranks[node] = rank;
+		}
+	}
+}
+
+/// Rank of the node before the label's on its path, 0 in row 0.
+fn predecessor_rank<W>(label: &TieLabel<W>, ranks: &[usize]) -> usize {
+	label.predecessor.map_or(0, |(pred, _)| ranks[pred])
+}
+
+/// Whether path `a` is better than path `b` to the same node, or row, given the
+/// rank of the prefix each of them extends.
+fn prefers<W: Weight>(
+	objective: Objective,
+	tie_breaks: &[TieBreak],
+	(a, a_rank): (&TieLabel<W>, usize),
+	(b, b_rank): (&TieLabel<W>, usize),
+) -> bool {
+	if objective.prefers(a.cost, b.cost) {
+		return true;
+	}
+	if objective.prefers(b.cost, a.cost) {
+		return false;
+	}
+
+	for tie_break in tie_breaks.iter().chain(Some(&TieBreak::Leftmost)) {
+		let ordering = match *tie_break {
+			TieBreak::ColumnChanges => a.column_changes.cmp(&b.column_changes),
+			// Resources no edge consumes are 0 on every path.
+			TieBreak::Resource(idx) => match (a.resources.get(idx), b.resources.get(idx)) {
+				(Some(a_resource), Some(b_resource)) => {
+					a_resource.partial_cmp(b_resource).unwrap_or(Ordering::Equal)
+				}
+				_ => Ordering::Equal,
+			},
+			TieBreak::Leftmost => a_rank.cmp(&b_rank),
+		};
+		if ordering != Ordering::Equal {
+			return ordering == Ordering::Less;
+		}
+	}
+	false
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{cell::RefCell, convert::TryFrom, rc::Rc};
+
+	use super::*;
+	use crate::{Edge, Node};
+
+	#[test]
+	fn breaks_ties_in_order() {
+		// Every path costs 2.
+		let row = vec![vec![(0, 1), (1, 1)], vec![(1, 1), (0, 1)]];
+		let graph = LayeredGraph::from_rows(&[row.clone(), row, vec![vec![], vec![]]]).unwrap();
+		let nodes = |query: Query| graph.query(&query.target(1)).unwrap().unwrap().nodes;
+
+		assert_eq!(nodes(Query::new().tie_break(TieBreak::Leftmost)), vec![(0, 0), (1, 0), (2, 1)]);
+		assert_eq!(
+			nodes(Query::new().tie_break(TieBreak::ColumnChanges)),
+			vec![(0, 1), (1, 1), (2, 1)]
+		);
+		// All paths have the same resources, so the leftmost one is taken.
+		assert_eq!(
+			nodes(Query::new().tie_break(TieBreak::Resource(0))),
+			vec![(0, 0), (1, 0), (2, 1)]
+		);
+	}
+
+	#[test]
+	fn breaks_ties_by_resource() {
+		let r1c0 = Rc::new(RefCell::new(Node::new(vec![])));
+		let r0c0 = Rc::new(RefCell::new(Node::new(vec![
+			Edge::with_resources(1, vec![0, 2], r1c0.clone()),
+			Edge::with_resources(1, vec![1, 1], r1c0.clone()),
+		])));
+		let graph = LayeredGraph::try_from(&vec![vec![r0c0], vec![r1c0]]).unwrap();
+
+		let edges = |query: Query| graph.query(&query).unwrap().unwrap().edges;
+		assert_eq!(edges(Query::new().tie_break(TieBreak::Resource(1))), vec![1]);
+		assert_eq!(edges(Query::new().tie_break(TieBreak::Resource(0))), vec![0]);
+		assert_eq!(
+			edges(Query::new().tie_break(TieBreak::Resource(1)).tie_break(TieBreak::Resource(0))),
+			vec![1]
+		);
+	}
+}
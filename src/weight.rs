@@ -0,0 +1,142 @@
+use std::fmt;
+
+/// Edge weight, and path cost, of the min path cost problem.
+///
+/// Weights only need a partial order so floats can be used, validation rejects
+/// the weights that are not `valid` (e.g. NaN) so the order is total in
+/// practice.
+pub trait Weight: Copy + PartialOrd {
+	/// Cost of a path without edges.
+	fn zero() -> Self;
+
+	/// `self + other`, `None` if the sum overflows.
+	fn checked_add(self, other: Self) -> Option<Self>;
+
+	/// `self + other`, clamped to the range of `Self`.
+	fn saturating_add(self, other: Self) -> Self;
+
+	/// Whether the weight can be compared with all other weights.
+	fn is_valid(self) -> bool {
+		true
+	}
+}
+
+macro_rules! impl_weight_for_integer {
+	($($t:ty),*) => {
+		$(
+			impl Weight for $t {
+				fn zero() -> Self {
+					0
+				}
+
+				fn checked_add(self, other: Self) -> Option<Self> {
+					<$t>::checked_add(self, other)
+				}
+
+				fn saturating_add(self, other: Self) -> Self {
+					<$t>::saturating_add(self, other)
+				}
+			}
+		)*
+	};
+}
+
+impl_weight_for_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_weight_for_float {
+	($($t:ty),*) => {
+		$(
+			impl Weight for $t {
+				fn zero() -> Self {
+					0.0
+				}
+
+				/// Sums of finite weights overflow when they are no longer finite.
+				fn checked_add(self, other: Self) -> Option<Self> {
+					let sum = self + other;
+					let overflows = sum.is_infinite() && self.is_finite() && other.is_finite();
+					if sum.is_nan() || overflows {
+						None
+					} else {
+						Some(sum)
+					}
+				}
+
+				/// Only sums of finite weights are clamped, infinite weights stay so.
+				fn saturating_add(self, other: Self) -> Self {
+					if self.is_finite() && other.is_finite() {
+						(self + other).max(<$t>::MIN).min(<$t>::MAX)
+					} else {
+						self + other
+					}
+				}
+
+				fn is_valid(self) -> bool {
+					!self.is_nan()
+				}
+			}
+		)*
+	};
+}
+
+impl_weight_for_float!(f32, f64);
+
+/// Fixed-point decimal with `SCALE` digits after the decimal point, stored as
+/// the integer `value * 10^SCALE`, e.g. `Fixed::<2>(1999)` is `19.99`.
+///
+/// Unlike floats, sums of `Fixed` are exact, which suits currency amounts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed<const SCALE: u32>(pub i64);
+
+impl<const SCALE: u32> Weight for Fixed<SCALE> {
+	fn zero() -> Self {
+		Fixed(0)
+	}
+
+	fn checked_add(self, other: Self) -> Option<Self> {
+		self.0.checked_add(other.0).map(Fixed)
+	}
+
+	fn saturating_add(self, other: Self) -> Self {
+		Fixed(self.0.saturating_add(other.0))
+	}
+}
+
+impl<const SCALE: u32> fmt::Display for Fixed<SCALE> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let sign = if self.0 < 0 { "-" } else { "" };
+		let unscaled = self.0.unsigned_abs();
+		if SCALE == 0 {
+			return write!(f, "{}{}", sign, unscaled);
+		}
+
+		let divisor = 10u64.pow(SCALE);
+		write!(
+			f,
+			"{}{}.{:0width$}",
+			sign,
+			unscaled / divisor,
+			unscaled % divisor,
+			width = SCALE as usize
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn float_overflow_and_fixed_display() {
+		assert_eq!(Weight::checked_add(f64::MAX, f64::MAX), None);
+		assert_eq!(Weight::checked_add(f64::INFINITY, 1.0), Some(f64::INFINITY));
+		assert_eq!(Weight::saturating_add(f64::MAX, f64::MAX), f64::MAX);
+		assert_eq!(Weight::saturating_add(f64::INFINITY, 1.0), f64::INFINITY);
+		assert_eq!(Weight::saturating_add(1.0, f64::NEG_INFINITY), f64::NEG_INFINITY);
+		assert!(!f64::NAN.is_valid());
+
+		assert_eq!(Fixed::<2>(1999).to_string(), "19.99");
+		assert_eq!(Fixed::<2>(-5).to_string(), "-0.05");
+		assert_eq!(Fixed::<0>(42).to_string(), "42");
+	}
+}